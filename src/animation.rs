@@ -1,7 +1,10 @@
+use crate::clock::Clock;
 use crate::git::{CommitMetadata, DiffHunk, FileChange, FileStatus, LineChangeType};
 use crate::syntax::Highlighter;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use std::cell::RefCell;
+use std::rc::Rc;
 use std::time::{Duration, Instant};
 use unicode_width::UnicodeWidthStr;
 
@@ -11,6 +14,8 @@ const CURSOR_MOVE_SHORT_MULTIPLIER: f64 = 1.0; // Speed for short distances (1-5
 const CURSOR_MOVE_MEDIUM_MULTIPLIER: f64 = 0.3; // Speed for medium distances (6-20 lines)
 const CURSOR_MOVE_LONG_MULTIPLIER: f64 = 0.1; // Speed for long distances (21+ lines)
 const DELETE_LINE_PAUSE: f64 = 10.0; // After deleting a line
+const DELETE_CHAR_SPEED: f64 = 0.6; // Backspacing a character (faster than typing)
+const DELETE_RANGE_PAUSE: f64 = 5.0; // After deleting a selected span within a line
 const INSERT_LINE_PAUSE: f64 = 6.7; // After inserting a line
 const HUNK_PAUSE: f64 = 50.0; // Between hunks
 const CHECKOUT_PAUSE: f64 = 16.7; // After git checkout command
@@ -27,6 +32,13 @@ const GIT_PUSH_PAUSE: f64 = 16.7; // After git push command
 const PUSH_OUTPUT_PAUSE: f64 = 10.0; // Between push output lines
 const PUSH_FINAL_PAUSE: f64 = 66.7; // After final push output
 
+// Intra-line edits
+const EDIT_MIN_SIMILARITY: f64 = 0.5; // Share of unchanged text for a line to be edited in place
+const DELETE_RANGE_MIN_CHARS: usize = 8; // Longer removals are selected and deleted at once
+const EDIT_MAX_TOKENS: usize = 200; // Longer lines are retyped instead of diffed
+
+const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(500);
+
 /// Represents the current state of the editor buffer
 #[derive(Debug, Clone)]
 pub struct EditorBuffer {
@@ -116,6 +128,27 @@ impl EditorBuffer {
             self.lines.push(String::new());
         }
     }
+
+    /// Delete the character at `col` (char index)
+    pub fn delete_char(&mut self, line: usize, col: usize) {
+        self.delete_range(line, col, col + 1);
+    }
+
+    /// Delete the characters from `start` to `end` (char indices, end exclusive)
+    pub fn delete_range(&mut self, line: usize, start: usize, end: usize) {
+        let Some(line_str) = self.lines.get_mut(line) else {
+            return;
+        };
+        let byte_idx = |col: usize| {
+            line_str
+                .char_indices()
+                .nth(col)
+                .map(|(idx, _)| idx)
+                .unwrap_or(line_str.len())
+        };
+        let range = byte_idx(start)..byte_idx(end);
+        line_str.replace_range(range, "");
+    }
 }
 
 /// Individual animation step
@@ -133,6 +166,17 @@ pub enum AnimationStep {
     DeleteLine {
         line: usize,
     },
+    /// Backspace the character at `col`
+    DeleteChar {
+        line: usize,
+        col: usize,
+    },
+    /// Delete a selected span of characters at once
+    DeleteRange {
+        line: usize,
+        start: usize,
+        end: usize,
+    },
     MoveCursor {
         line: usize,
         col: usize,
@@ -145,6 +189,7 @@ pub enum AnimationStep {
         old_content: String,
         new_content: String,
         path: String,
+        language: Option<String>,
     },
     OpenFileDialogStart,
     DialogTypeChar {
@@ -175,103 +220,14 @@ pub enum ActivePane {
     Terminal,
 }
 
-/// Main animation engine
-pub struct AnimationEngine {
-    pub buffer: EditorBuffer,
-    pub state: AnimationState,
+/// Generates the animation steps for a commit
+/// Independent of engine state so steps can be prepared ahead of time on another thread
+pub struct StepBuilder {
     steps: Vec<AnimationStep>,
-    current_step: usize,
-    last_update: Instant,
     speed_ms: u64,
-    next_step_delay: u64,
-    pause_until: Option<Instant>,
-    pub cursor_visible: bool,
-    cursor_blink_timer: Instant,
-    viewport_height: usize,
-    content_width: usize,
-    pub current_file_index: usize,
-    pub current_file_path: Option<String>,
-    pub terminal_lines: Vec<String>,
-    pub active_pane: ActivePane,
-    pub highlighter: RefCell<Highlighter>,
-    /// Track cumulative line offset from old_content (insertions - deletions)
-    pub line_offset: isize,
-    /// Target frames per second for rendering
-    #[allow(dead_code)]
-    target_fps: u64,
-    /// Frame interval in milliseconds (calculated from target_fps)
-    frame_interval_ms: u64,
-    /// Last frame render time
-    last_frame: Instant,
-    /// Dialog title (e.g., "Open File...")
-    pub dialog_title: Option<String>,
-    /// Text being typed in the dialog
-    pub dialog_typing_text: String,
-    /// Current metadata being displayed
-    current_metadata: Option<CommitMetadata>,
-    /// Pending metadata to be applied on ResetState
-    pending_metadata: Option<CommitMetadata>,
 }
 
-impl AnimationEngine {
-    pub fn new(speed_ms: u64) -> Self {
-        let target_fps: u64 = 120;
-        let frame_interval_ms = 1000 / target_fps;
-        let now = Instant::now();
-        Self {
-            buffer: EditorBuffer::new(),
-            state: AnimationState::Idle,
-            steps: Vec::new(),
-            current_step: 0,
-            last_update: now,
-            speed_ms,
-            next_step_delay: speed_ms,
-            pause_until: None,
-            cursor_visible: true,
-            cursor_blink_timer: now,
-            viewport_height: 20, // Default, will be updated from UI
-            content_width: 80,   // Default, will be updated from UI
-            current_file_index: 0,
-            current_file_path: None,
-            terminal_lines: Vec::new(),
-            active_pane: ActivePane::Terminal, // Start with terminal (git checkout)
-            highlighter: RefCell::new(Highlighter::new()),
-            line_offset: 0,
-            target_fps,
-            frame_interval_ms,
-            last_frame: now,
-            dialog_title: None,
-            dialog_typing_text: String::new(),
-            current_metadata: None,
-            pending_metadata: None,
-        }
-    }
-
-    pub fn set_viewport_height(&mut self, height: usize) {
-        self.viewport_height = height;
-    }
-
-    pub fn set_content_width(&mut self, width: usize) {
-        self.content_width = width;
-    }
-
-    /// Get the current metadata being displayed
-    pub fn current_metadata(&self) -> Option<&CommitMetadata> {
-        self.current_metadata.as_ref()
-    }
-
-    fn calculate_line_offsets(content: &str) -> Vec<usize> {
-        std::iter::once(0)
-            .chain(content.bytes().enumerate().filter_map(|(i, b)| {
-                if b == b'\n' {
-                    Some(i + 1)
-                } else {
-                    None
-                }
-            }))
-            .collect()
-    }
-
+impl StepBuilder {
     /// Add a terminal command with typing animation
     fn add_terminal_command(&mut self, command: &str) {
         self.steps.push(AnimationStep::TerminalPrompt);
@@ -280,19 +236,23 @@ impl AnimationEngine {
         }
     }
 
-    /// Load a commit and generate animation steps
-    pub fn load_commit(&mut self, metadata: &CommitMetadata) {
-        // Store pending metadata to be applied on ResetState
-        self.pending_metadata = Some(metadata.clone());
-
-        self.steps.clear();
-        self.current_step = 0;
-        self.state = AnimationState::Playing;
-        self.last_update = Instant::now();
-        self.pause_until = None;
+    /// Generate animation steps for a whole commit
+    pub fn build(metadata: &CommitMetadata, speed_ms: u64) -> Vec<AnimationStep> {
+        let mut builder = Self {
+            steps: Vec::new(),
+            speed_ms,
+        };
+        builder.add_commit(metadata);
+        builder.steps
+    }
 
+    fn add_commit(&mut self, metadata: &CommitMetadata) {
         // Time travel to commit date
-        let parent_hash = format!("{}^", &metadata.hash[..7]);
+        // Uncommitted changes have no hash to show, their patch id only tells them apart
+        let location = match metadata.uncommitted {
+            Some(_) => format!("{} changes", metadata.short_id()),
+            None => format!("commit {}", metadata.short_id()),
+        };
         let datetime_str = metadata.date.format("%Y-%m-%d %H:%M:%S").to_string();
         self.add_terminal_command(&format!("time-travel {}", datetime_str));
         self.steps.push(AnimationStep::Pause {
@@ -314,11 +274,7 @@ impl AnimationEngine {
             text: format!("🕰️  Arrived at {}", datetime_str),
         });
         self.steps.push(AnimationStep::TerminalOutput {
-            text: format!(
-                "📍 Location: commit {} by {}",
-                &metadata.hash[..7],
-                metadata.author
-            ),
+            text: format!("📍 Location: {} by {}", location, metadata.author),
         });
         self.steps.push(AnimationStep::Pause {
             duration_ms: (self.speed_ms as f64 * CHECKOUT_OUTPUT_PAUSE) as u64,
@@ -333,6 +289,10 @@ impl AnimationEngine {
         // Process all file changes in sorted order
         for &index in &sorted_indices {
             let change = &metadata.changes[index];
+            // Files outside --path only appear in the file tree
+            if change.is_out_of_scope {
+                continue;
+            }
             match (change.is_excluded, &change.status) {
                 // Skip excluded files (lock files and generated files)
                 (true, _) => {
@@ -344,6 +304,7 @@ impl AnimationEngine {
                         old_content,
                         new_content,
                         path: change.path.clone(),
+                        language: change.language.clone(),
                     });
 
                     self.steps.push(AnimationStep::Pause {
@@ -369,6 +330,7 @@ impl AnimationEngine {
                         old_content,
                         new_content: String::new(),
                         path: change.path.clone(),
+                        language: change.language.clone(),
                     });
 
                     self.steps.push(AnimationStep::Pause {
@@ -393,6 +355,7 @@ impl AnimationEngine {
                         old_content,
                         new_content,
                         path: change.path.clone(),
+                        language: change.language.clone(),
                     });
 
                     self.steps.push(AnimationStep::Pause {
@@ -444,6 +407,7 @@ impl AnimationEngine {
                         old_content,
                         new_content,
                         path: change.path.clone(),
+                        language: change.language.clone(),
                     });
 
                     // Add pause before starting file animation
@@ -467,13 +431,14 @@ impl AnimationEngine {
         }
 
         // Git commit
+        let branch = metadata.ref_name.as_deref().unwrap_or("HEAD");
         let commit_message = metadata.message.lines().next().unwrap_or("Update");
         self.add_terminal_command(&format!("git commit -m \"{}\"", commit_message));
         self.steps.push(AnimationStep::Pause {
             duration_ms: (self.speed_ms as f64 * GIT_COMMIT_PAUSE) as u64,
         });
         self.steps.push(AnimationStep::TerminalOutput {
-            text: format!("💾 [main {}] {}", &metadata.hash[..7], commit_message),
+            text: format!("💾 [{} {}] {}", branch, metadata.short_id(), commit_message),
         });
         self.steps.push(AnimationStep::TerminalOutput {
             text: format!(
@@ -487,7 +452,7 @@ impl AnimationEngine {
         });
 
         // Git push
-        self.add_terminal_command("git push origin main");
+        self.add_terminal_command(&format!("git push origin {}", branch));
         self.steps.push(AnimationStep::Pause {
             duration_ms: (self.speed_ms as f64 * GIT_PUSH_PAUSE) as u64,
         });
@@ -510,24 +475,25 @@ impl AnimationEngine {
             duration_ms: (self.speed_ms as f64 * GIT_PUSH_PAUSE) as u64,
         });
         self.steps.push(AnimationStep::TerminalOutput {
-            text: "📡 Beaming to origin/main via satellite...".to_string(),
+            text: format!("📡 Beaming to origin/{} via satellite...", branch),
         });
         self.steps.push(AnimationStep::Pause {
             duration_ms: (self.speed_ms as f64 * PUSH_OUTPUT_PAUSE) as u64,
         });
         self.steps.push(AnimationStep::TerminalOutput {
-            text: format!(
-                "   {}..{} ✨ SUCCESS",
-                &parent_hash[..7],
-                &metadata.hash[..7]
-            ),
+            text: match &metadata.parent_hash {
+                Some(parent) => format!(
+                    "   {}..{} ✨ SUCCESS",
+                    &parent[..7.min(parent.len())],
+                    metadata.short_id()
+                ),
+                // Root commits start a new branch
+                None => format!("   * [new branch] {} ✨ SUCCESS", metadata.short_id()),
+            },
         });
         self.steps.push(AnimationStep::Pause {
             duration_ms: (self.speed_ms as f64 * PUSH_FINAL_PAUSE) as u64,
         });
-
-        // Start with empty editor (no file opened yet)
-        self.buffer = EditorBuffer::new();
     }
 
     /// Generate animation steps for a file change
@@ -654,41 +620,50 @@ impl AnimationEngine {
         let mut buffer_line = start_buffer_line;
         let mut cursor_line = start_cursor_line;
 
-        for line_change in &hunk.lines {
+        let mut lines = hunk.lines.iter().peekable();
+        while let Some(line_change) = lines.next() {
             match line_change.change_type {
                 LineChangeType::Deletion => {
-                    // Delete the entire line at current buffer position
-                    self.steps
-                        .push(AnimationStep::DeleteLine { line: buffer_line });
-                    self.steps.push(AnimationStep::Pause {
-                        duration_ms: (self.speed_ms as f64 * DELETE_LINE_PAUSE) as u64,
-                    });
-                    cursor_line = buffer_line;
-                    // After deletion, buffer_line stays the same
-                    // (the next line moves up to this position)
-                }
-                LineChangeType::Addition => {
-                    // Insert empty line at current buffer position
-                    self.steps.push(AnimationStep::InsertLine {
-                        line: buffer_line,
-                        content: String::new(),
-                    });
-
-                    // Type each character
-                    for (col, ch) in line_change.content.chars().enumerate() {
-                        self.steps.push(AnimationStep::InsertChar {
-                            line: buffer_line,
-                            col,
-                            ch,
-                        });
+                    // Pair a run of deletions with the additions that follow it, so that
+                    // lines that only changed a little are edited in place
+                    let mut deletions = vec![line_change];
+                    while let Some(next) =
+                        lines.next_if(|l| matches!(l.change_type, LineChangeType::Deletion))
+                    {
+                        deletions.push(next);
+                    }
+                    let mut additions = Vec::new();
+                    while let Some(next) =
+                        lines.next_if(|l| matches!(l.change_type, LineChangeType::Addition))
+                    {
+                        additions.push(next);
                     }
 
+                    for (deletion, addition) in deletions.iter().zip(&additions) {
+                        match diff_line(&deletion.content, &addition.content) {
+                            Some(edits) => self.generate_steps_for_line_edit(buffer_line, &edits),
+                            None => {
+                                self.generate_line_deletion(buffer_line);
+                                self.generate_line_insertion(buffer_line, &addition.content);
+                            }
+                        }
+                        cursor_line = buffer_line;
+                        buffer_line += 1;
+                    }
+                    for _ in additions.len()..deletions.len() {
+                        self.generate_line_deletion(buffer_line);
+                        cursor_line = buffer_line;
+                    }
+                    for addition in additions.iter().skip(deletions.len()) {
+                        self.generate_line_insertion(buffer_line, &addition.content);
+                        cursor_line = buffer_line;
+                        buffer_line += 1;
+                    }
+                }
+                LineChangeType::Addition => {
+                    self.generate_line_insertion(buffer_line, &line_change.content);
                     cursor_line = buffer_line;
                     buffer_line += 1; // Move to next line after insertion
-
-                    self.steps.push(AnimationStep::Pause {
-                        duration_ms: (self.speed_ms as f64 * INSERT_LINE_PAUSE) as u64,
-                    });
                 }
                 LineChangeType::Context => {
                     // Move cursor to next line if needed
@@ -710,10 +685,356 @@ impl AnimationEngine {
         (cursor_line, buffer_line)
     }
 
+    /// Delete the entire line (the next line moves up to this position)
+    fn generate_line_deletion(&mut self, line: usize) {
+        self.steps.push(AnimationStep::DeleteLine { line });
+        self.steps.push(AnimationStep::Pause {
+            duration_ms: (self.speed_ms as f64 * DELETE_LINE_PAUSE) as u64,
+        });
+    }
+
+    /// Insert an empty line and type its content (without the line ending)
+    fn generate_line_insertion(&mut self, line: usize, content: &str) {
+        self.steps.push(AnimationStep::InsertLine {
+            line,
+            content: String::new(),
+        });
+        let content = content.trim_end_matches(['\n', '\r']);
+        for (col, ch) in content.chars().enumerate() {
+            self.steps.push(AnimationStep::InsertChar { line, col, ch });
+        }
+        self.steps.push(AnimationStep::Pause {
+            duration_ms: (self.speed_ms as f64 * INSERT_LINE_PAUSE) as u64,
+        });
+    }
+
+    /// Move to each changed span, backspace (or select and delete) the removed
+    /// characters and type the new ones
+    fn generate_steps_for_line_edit(&mut self, line: usize, edits: &[LineEdit]) {
+        for edit in edits {
+            let end = edit.col + edit.removed;
+            self.steps
+                .push(AnimationStep::MoveCursor { line, col: end });
+            self.steps.push(AnimationStep::Pause {
+                duration_ms: (self.speed_ms as f64 * CURSOR_MOVE_PAUSE) as u64,
+            });
+
+            if edit.removed >= DELETE_RANGE_MIN_CHARS {
+                self.steps.push(AnimationStep::DeleteRange {
+                    line,
+                    start: edit.col,
+                    end,
+                });
+                self.steps.push(AnimationStep::Pause {
+                    duration_ms: (self.speed_ms as f64 * DELETE_RANGE_PAUSE) as u64,
+                });
+            } else {
+                for col in (edit.col..end).rev() {
+                    self.steps.push(AnimationStep::DeleteChar { line, col });
+                }
+            }
+
+            for (offset, ch) in edit.inserted.chars().enumerate() {
+                self.steps.push(AnimationStep::InsertChar {
+                    line,
+                    col: edit.col + offset,
+                    ch,
+                });
+            }
+        }
+        self.steps.push(AnimationStep::Pause {
+            duration_ms: (self.speed_ms as f64 * INSERT_LINE_PAUSE) as u64,
+        });
+    }
+}
+
+/// A change within a line, applied left to right: at char column `col` of the
+/// partly edited line, `removed` characters are replaced by `inserted`
+#[derive(Debug, Clone, PartialEq)]
+struct LineEdit {
+    col: usize,
+    removed: usize,
+    inserted: String,
+}
+
+/// Word-level diff of a changed line, or `None` if too little of it is kept
+/// for editing in place to look natural
+fn diff_line(old: &str, new: &str) -> Option<Vec<LineEdit>> {
+    let old = old.trim_end_matches(['\n', '\r']);
+    let new = new.trim_end_matches(['\n', '\r']);
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    if old_tokens.len() > EDIT_MAX_TOKENS || new_tokens.len() > EDIT_MAX_TOKENS {
+        return None;
+    }
+
+    // Longest common subsequence of tokens
+    let (n, m) = (old_tokens.len(), new_tokens.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old_tokens[i] == new_tokens[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut edits: Vec<LineEdit> = Vec::new();
+    let mut kept = 0;
+    let mut col = 0;
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old_tokens[i] == new_tokens[j] {
+            let len = old_tokens[i].chars().count();
+            kept += len;
+            col += len;
+            i += 1;
+            j += 1;
+            continue;
+        }
+
+        // Extend the edit at this column, or start a new one
+        let edit = match edits.last_mut() {
+            Some(edit) if edit.col + edit.inserted.chars().count() == col => edit,
+            _ => {
+                edits.push(LineEdit {
+                    col,
+                    removed: 0,
+                    inserted: String::new(),
+                });
+                edits.last_mut().unwrap()
+            }
+        };
+        if j < m && (i == n || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            edit.inserted.push_str(new_tokens[j]);
+            col += new_tokens[j].chars().count();
+            j += 1;
+        } else {
+            edit.removed += old_tokens[i].chars().count();
+            i += 1;
+        }
+    }
+
+    let total = old.chars().count() + new.chars().count();
+    let similarity = if total == 0 {
+        1.0
+    } else {
+        2.0 * kept as f64 / total as f64
+    };
+    (similarity >= EDIT_MIN_SIMILARITY).then_some(edits)
+}
+
+/// Split a line into words, runs of whitespace and single other characters
+fn tokenize(line: &str) -> Vec<&str> {
+    let class = |ch: char| {
+        if ch.is_alphanumeric() || ch == '_' {
+            1
+        } else if ch.is_whitespace() {
+            2
+        } else {
+            0
+        }
+    };
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut previous = None;
+    for (idx, ch) in line.char_indices() {
+        let kind = class(ch);
+        if idx > start && (kind == 0 || previous != Some(kind)) {
+            tokens.push(&line[start..idx]);
+            start = idx;
+        }
+        previous = Some(kind);
+    }
+    if start < line.len() {
+        tokens.push(&line[start..]);
+    }
+    tokens
+}
+
+/// Main animation engine
+pub struct AnimationEngine {
+    pub buffer: EditorBuffer,
+    pub state: AnimationState,
+    steps: Vec<AnimationStep>,
+    current_step: usize,
+    last_update: Instant,
+    speed_ms: u64,
+    next_step_delay: u64,
+    pause_until: Option<Instant>,
+    pub cursor_visible: bool,
+    cursor_blink_timer: Instant,
+    viewport_height: usize,
+    content_width: usize,
+    pub current_file_index: usize,
+    pub current_file_path: Option<String>,
+    pub terminal_lines: Vec<String>,
+    pub active_pane: ActivePane,
+    pub highlighter: RefCell<Highlighter>,
+    /// Track cumulative line offset from old_content (insertions - deletions)
+    pub line_offset: isize,
+    /// Target frames per second for rendering
+    #[allow(dead_code)]
+    target_fps: u64,
+    /// Frame interval in milliseconds (calculated from target_fps)
+    frame_interval_ms: u64,
+    /// Last frame render time
+    last_frame: Instant,
+    /// Dialog title (e.g., "Open File...")
+    pub dialog_title: Option<String>,
+    /// Text being typed in the dialog
+    pub dialog_typing_text: String,
+    /// Current metadata being displayed
+    current_metadata: Option<CommitMetadata>,
+    /// Pending metadata to be applied on ResetState
+    pending_metadata: Option<CommitMetadata>,
+    /// Typing speed the current steps were generated with (pauses scale relative to it)
+    steps_speed_ms: u64,
+    /// When playback was paused by the user, if it is paused
+    paused_at: Option<Instant>,
+    /// Time source for step delays, pauses and cursor blinking
+    clock: Rc<dyn Clock>,
+    /// Varies typing delays; seeded for reproducible playback
+    /// A named algorithm, as `StdRng` output may change between rand releases
+    rng: ChaCha8Rng,
+}
+
+impl AnimationEngine {
+    /// Create an engine whose step delays and pauses are timed by `clock`
+    pub fn new(speed_ms: u64, clock: Rc<dyn Clock>) -> Self {
+        let target_fps: u64 = 120;
+        let frame_interval_ms = 1000 / target_fps;
+        let now = clock.now();
+        Self {
+            buffer: EditorBuffer::new(),
+            state: AnimationState::Idle,
+            steps: Vec::new(),
+            current_step: 0,
+            last_update: now,
+            speed_ms,
+            next_step_delay: speed_ms,
+            pause_until: None,
+            cursor_visible: true,
+            cursor_blink_timer: now,
+            viewport_height: 20, // Default, will be updated from UI
+            content_width: 80,   // Default, will be updated from UI
+            current_file_index: 0,
+            current_file_path: None,
+            terminal_lines: Vec::new(),
+            active_pane: ActivePane::Terminal, // Start with terminal (git checkout)
+            highlighter: RefCell::new(Highlighter::new()),
+            line_offset: 0,
+            target_fps,
+            frame_interval_ms,
+            last_frame: now,
+            dialog_title: None,
+            dialog_typing_text: String::new(),
+            current_metadata: None,
+            pending_metadata: None,
+            steps_speed_ms: speed_ms,
+            paused_at: None,
+            clock,
+            rng: ChaCha8Rng::from_os_rng(),
+        }
+    }
+
+    /// Make typing delays repeat the same variation for the same seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    pub fn set_viewport_height(&mut self, height: usize) {
+        self.viewport_height = height;
+    }
+
+    pub fn set_content_width(&mut self, width: usize) {
+        self.content_width = width;
+    }
+
+    pub fn speed_ms(&self) -> u64 {
+        self.speed_ms
+    }
+
+    /// Change typing speed live; pauses of already generated steps are scaled accordingly
+    pub fn set_speed(&mut self, speed_ms: u64) {
+        self.speed_ms = speed_ms.max(1);
+    }
+
+    /// Pause or resume playback
+    pub fn set_paused(&mut self, paused: bool) {
+        match (paused, self.paused_at) {
+            (true, None) => self.paused_at = Some(self.clock.now()),
+            (false, Some(paused_at)) => {
+                // Shift pending timers so the pause does not eat into them
+                let paused_for = self.clock.now().duration_since(paused_at);
+                if let Some(pause_until) = self.pause_until.as_mut() {
+                    *pause_until += paused_for;
+                }
+                self.last_update += paused_for;
+                self.paused_at = None;
+            }
+            _ => {}
+        }
+    }
+
+    pub fn is_user_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Get the current metadata being displayed
+    pub fn current_metadata(&self) -> Option<&CommitMetadata> {
+        self.current_metadata.as_ref()
+    }
+
+    fn calculate_line_offsets(content: &str) -> Vec<usize> {
+        std::iter::once(0)
+            .chain(content.bytes().enumerate().filter_map(|(i, b)| {
+                if b == b'\n' {
+                    Some(i + 1)
+                } else {
+                    None
+                }
+            }))
+            .collect()
+    }
+
+    /// Load a commit and generate animation steps
+    pub fn load_commit(&mut self, metadata: &CommitMetadata) {
+        let steps = StepBuilder::build(metadata, self.speed_ms);
+        self.load_steps(metadata, steps, self.speed_ms);
+    }
+
+    /// Load a commit with steps generated ahead of time at `steps_speed_ms`
+    pub fn load_steps(
+        &mut self,
+        metadata: &CommitMetadata,
+        steps: Vec<AnimationStep>,
+        steps_speed_ms: u64,
+    ) {
+        // Store pending metadata to be applied on ResetState
+        self.pending_metadata = Some(metadata.clone());
+
+        self.steps = steps;
+        self.current_step = 0;
+        self.state = AnimationState::Playing;
+        self.last_update = self.clock.now();
+        self.pause_until = None;
+        self.steps_speed_ms = steps_speed_ms;
+
+        // Start with empty editor (no file opened yet)
+        self.buffer = EditorBuffer::new();
+    }
+
     /// Update animation state and return true if display needs refresh
     pub fn tick(&mut self) -> bool {
         self.update_cursor_blink();
 
+        if self.is_user_paused() {
+            return true;
+        }
+
         if self.is_paused() {
             return true;
         }
@@ -722,7 +1043,7 @@ impl AnimationEngine {
             return false;
         }
 
-        let now = Instant::now();
+        let now = self.clock.now();
         if !self.should_render_frame(now) {
             return false;
         }
@@ -737,15 +1058,15 @@ impl AnimationEngine {
     }
 
     fn update_cursor_blink(&mut self) {
-        if self.cursor_blink_timer.elapsed() >= Duration::from_millis(500) {
+        if self.clock.now().duration_since(self.cursor_blink_timer) >= CURSOR_BLINK_INTERVAL {
             self.cursor_visible = !self.cursor_visible;
-            self.cursor_blink_timer = Instant::now();
+            self.cursor_blink_timer = self.clock.now();
         }
     }
 
     fn is_paused(&mut self) -> bool {
         if let Some(pause_until) = self.pause_until {
-            if Instant::now() < pause_until {
+            if self.clock.now() < pause_until {
                 return true;
             }
             self.pause_until = None;
@@ -753,6 +1074,27 @@ impl AnimationEngine {
         false
     }
 
+    /// End of the pause currently in progress, if any
+    pub fn pause_until(&self) -> Option<Instant> {
+        self.pause_until
+    }
+
+    /// Earliest time at which `tick` can change the display
+    /// Lets callers on a virtual clock jump straight to the next frame
+    pub fn next_deadline(&self) -> Instant {
+        let blink = self.cursor_blink_timer + CURSOR_BLINK_INTERVAL;
+        if self.state != AnimationState::Playing || self.is_user_paused() {
+            return blink;
+        }
+
+        let step = match self.pause_until {
+            Some(pause_until) => pause_until,
+            None => (self.last_update + Duration::from_millis(self.next_step_delay))
+                .max(self.last_frame + Duration::from_millis(self.frame_interval_ms)),
+        };
+        step.min(blink)
+    }
+
     fn should_render_frame(&self, now: Instant) -> bool {
         now.duration_since(self.last_frame) >= Duration::from_millis(self.frame_interval_ms)
     }
@@ -776,7 +1118,7 @@ impl AnimationEngine {
         }
 
         if executed_any {
-            self.last_update = Instant::now();
+            self.last_update = self.clock.now();
             self.last_frame = frame_start;
         }
 
@@ -786,7 +1128,8 @@ impl AnimationEngine {
     fn can_execute_step(&self, executed_any: bool, accumulated_delay: u64) -> bool {
         // First step: check if enough time has elapsed since last step
         if !executed_any {
-            return self.last_update.elapsed() >= Duration::from_millis(self.next_step_delay);
+            return self.clock.now().duration_since(self.last_update)
+                >= Duration::from_millis(self.next_step_delay);
         }
 
         // Subsequent steps: check if they fit within frame budget
@@ -795,13 +1138,17 @@ impl AnimationEngine {
 
     fn execute_step(&mut self, step: AnimationStep) {
         // Calculate delay for next step with randomization for typing steps
-        let mut rng = rand::rng();
+        let rng = &mut self.rng;
         self.next_step_delay = match &step {
             AnimationStep::InsertChar { .. } | AnimationStep::TerminalTypeChar { .. } => {
                 // Add 70-130% variation to typing speed
                 let variation = rng.random_range(0.7..=1.3);
                 ((self.speed_ms as f64) * variation) as u64
             }
+            AnimationStep::DeleteChar { .. } => {
+                let variation = rng.random_range(0.7..=1.3);
+                ((self.speed_ms as f64) * DELETE_CHAR_SPEED * variation) as u64
+            }
             AnimationStep::DialogTypeChar { .. } => {
                 // Dialog typing is slower (2x speed with variation)
                 let variation = rng.random_range(0.7..=1.3);
@@ -838,13 +1185,27 @@ impl AnimationEngine {
                 // Track line offset for old_highlights mapping
                 self.line_offset -= 1;
             }
+            AnimationStep::DeleteChar { line, col } => {
+                self.active_pane = ActivePane::Editor;
+                self.buffer.delete_char(line, col);
+                self.buffer.cursor_line = line;
+                self.buffer.cursor_col = col;
+            }
+            AnimationStep::DeleteRange { line, start, end } => {
+                self.active_pane = ActivePane::Editor;
+                self.buffer.delete_range(line, start, end);
+                self.buffer.cursor_line = line;
+                self.buffer.cursor_col = start;
+            }
             AnimationStep::MoveCursor { line, col } => {
                 self.active_pane = ActivePane::Editor;
                 self.buffer.cursor_line = line;
                 self.buffer.cursor_col = col;
             }
             AnimationStep::Pause { duration_ms } => {
-                self.pause_until = Some(Instant::now() + Duration::from_millis(duration_ms));
+                // Scale pause to the current speed in case it changed after steps were generated
+                let duration_ms = duration_ms * self.speed_ms / self.steps_speed_ms.max(1);
+                self.pause_until = Some(self.clock.now() + Duration::from_millis(duration_ms));
             }
             AnimationStep::OpenFileDialogStart => {
                 self.dialog_typing_text = String::new();
@@ -858,6 +1219,7 @@ impl AnimationEngine {
                 old_content,
                 new_content,
                 path,
+                language,
             } => {
                 self.active_pane = ActivePane::Editor;
                 // Clear dialog when file is actually switched
@@ -870,7 +1232,16 @@ impl AnimationEngine {
 
                 // Update syntax highlighter for new file
                 // This will clear language settings if not supported
-                self.highlighter.borrow_mut().set_language_from_path(&path);
+                let content = if new_content.is_empty() {
+                    &old_content
+                } else {
+                    &new_content
+                };
+                self.highlighter.borrow_mut().set_language_for_file(
+                    &path,
+                    content,
+                    language.as_deref(),
+                );
 
                 // Pre-calculate highlights for both old and new content
                 self.buffer.old_highlights = self.highlighter.borrow_mut().highlight(&old_content);
@@ -999,7 +1370,291 @@ impl AnimationEngine {
         self.buffer.scroll_offset = logical_offset;
     }
 
+    /// Execute the next visible step immediately, skipping over pauses
+    pub fn step_forward(&mut self) -> bool {
+        let mut executed = false;
+        while self.current_step < self.steps.len() {
+            let step = self.steps[self.current_step].clone();
+            let is_pause = matches!(step, AnimationStep::Pause { .. });
+            self.execute_step(step);
+            self.current_step += 1;
+            executed = true;
+            if !is_pause {
+                break;
+            }
+        }
+        self.finish_seek();
+        executed
+    }
+
+    /// Jump to the next file's SwitchFile step
+    pub fn skip_to_next_file(&mut self) -> bool {
+        let next = (self.current_step..self.steps.len())
+            .find(|&i| matches!(self.steps[i], AnimationStep::SwitchFile { .. }));
+        match next {
+            Some(target) => {
+                self.seek_to_step(target);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Jump back to the previous file's SwitchFile step
+    /// Restarts the current file if it is the first one
+    pub fn skip_to_previous_file(&mut self) -> bool {
+        let switch_steps: Vec<usize> = (0..self.current_step)
+            .filter(|&i| matches!(self.steps[i], AnimationStep::SwitchFile { .. }))
+            .collect();
+        let target = match switch_steps.as_slice() {
+            [] => return false,
+            [.., previous, _current] => *previous,
+            [current] => *current,
+        };
+        self.seek_to_step(target);
+        true
+    }
+
+    /// Move playback so that `target` is the next step to execute
+    /// Earlier state is rebuilt by replaying steps from the start without delays
+    pub fn seek_to_step(&mut self, target: usize) {
+        let target = target.min(self.steps.len());
+
+        if target < self.current_step {
+            if self.pending_metadata.is_none() {
+                self.pending_metadata = self.current_metadata.clone();
+            }
+            self.current_step = 0;
+            self.buffer = EditorBuffer::new();
+            self.terminal_lines.clear();
+            self.current_file_index = 0;
+            self.current_file_path = None;
+            self.dialog_title = None;
+            self.dialog_typing_text = String::new();
+            self.active_pane = ActivePane::Terminal;
+            self.line_offset = 0;
+        }
+
+        while self.current_step < target {
+            let step = self.steps[self.current_step].clone();
+            self.execute_step(step);
+            self.current_step += 1;
+        }
+
+        self.finish_seek();
+    }
+
+    fn finish_seek(&mut self) {
+        self.pause_until = None;
+        self.last_update = self.clock.now();
+        if self.paused_at.is_some() {
+            self.paused_at = Some(self.last_update);
+        }
+        self.state = if self.current_step >= self.steps.len() {
+            AnimationState::Finished
+        } else {
+            AnimationState::Playing
+        };
+    }
+
+    /// Steps of the loaded commit
+    #[cfg(test)]
+    pub fn steps(&self) -> &[AnimationStep] {
+        &self.steps
+    }
+
+    /// Index of the next step to execute
+    pub fn current_step(&self) -> usize {
+        self.current_step
+    }
+
     pub fn is_finished(&self) -> bool {
         self.state == AnimationState::Finished
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::VirtualClock;
+    use crate::git::LineChange;
+
+    fn edit(col: usize, removed: usize, inserted: &str) -> LineEdit {
+        LineEdit {
+            col,
+            removed,
+            inserted: inserted.to_string(),
+        }
+    }
+
+    /// Commit without file changes, for driving the engine with hand-written steps
+    fn empty_commit() -> CommitMetadata {
+        CommitMetadata {
+            hash: "abc1234".to_string(),
+            parent_hash: None,
+            author: "Ghost".to_string(),
+            date: chrono::Utc::now(),
+            message: "Test".to_string(),
+            changes: Vec::new(),
+            merge: None,
+            ref_name: None,
+            uncommitted: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_line() {
+        assert_eq!(
+            tokenize("let x = foo(1);"),
+            vec!["let", " ", "x", " ", "=", " ", "foo", "(", "1", ")", ";"]
+        );
+        assert_eq!(
+            diff_line("let count = 1;\n", "let total = 2;\n"),
+            Some(vec![edit(4, 5, "total"), edit(12, 1, "2")])
+        );
+        assert_eq!(
+            diff_line("fn run(a: i32) {", "fn run(a: i32, b: i32) {"),
+            Some(vec![edit(13, 0, ", b: i32")])
+        );
+        // Mostly rewritten lines are deleted and retyped
+        assert_eq!(diff_line("return None;", "println!(\"done\");"), None);
+    }
+
+    #[test]
+    fn test_paired_lines_are_edited_in_place() {
+        let line = |change_type, content: &str| LineChange {
+            change_type,
+            content: format!("{}\n", content),
+            old_line_no: None,
+            new_line_no: None,
+        };
+        let hunk = DiffHunk {
+            old_start: 1,
+            old_lines: 3,
+            new_start: 1,
+            new_lines: 3,
+            lines: vec![
+                line(LineChangeType::Deletion, "let timeout = 30;"),
+                line(LineChangeType::Deletion, "retry(3);"),
+                line(LineChangeType::Addition, "let timeout = 60;"),
+                line(
+                    LineChangeType::Addition,
+                    "if enabled { start_server_with_defaults(); }",
+                ),
+                line(LineChangeType::Context, "done();"),
+            ],
+        };
+        let mut builder = StepBuilder {
+            steps: Vec::new(),
+            speed_ms: 10,
+        };
+        builder.generate_steps_for_hunk(&hunk, 0, 0);
+
+        let mut buffer = EditorBuffer::from_content("let timeout = 30;\nretry(3);\ndone();\n");
+        for step in &builder.steps {
+            match step {
+                AnimationStep::InsertChar { line, col, ch } => buffer.insert_char(*line, *col, *ch),
+                AnimationStep::InsertLine { line, content } => {
+                    buffer.insert_line(*line, content.clone())
+                }
+                AnimationStep::DeleteLine { line } => buffer.delete_line(*line),
+                AnimationStep::DeleteChar { line, col } => buffer.delete_char(*line, *col),
+                AnimationStep::DeleteRange { line, start, end } => {
+                    buffer.delete_range(*line, *start, *end)
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(
+            buffer.lines,
+            vec![
+                "let timeout = 60;",
+                "if enabled { start_server_with_defaults(); }",
+                "done();"
+            ]
+        );
+
+        // The first line is patched by backspacing "30" and typing "60"
+        let deleted =
+            |step: &&AnimationStep| matches!(step, AnimationStep::DeleteChar { line: 0, .. });
+        assert_eq!(builder.steps.iter().filter(deleted).count(), 2);
+        assert_eq!(
+            builder
+                .steps
+                .iter()
+                .filter(|step| matches!(step, AnimationStep::DeleteLine { .. }))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_virtual_clock_drives_playback() {
+        let clock = VirtualClock::new();
+        let mut engine = AnimationEngine::new(10, Rc::new(clock.clone()));
+        let metadata = empty_commit();
+        let steps = vec![
+            AnimationStep::TerminalPrompt,
+            AnimationStep::Pause { duration_ms: 100 },
+            AnimationStep::TerminalTypeChar { ch: 'x' },
+        ];
+        engine.load_steps(&metadata, steps, 10);
+
+        // Nothing happens until the clock moves
+        assert!(!engine.tick());
+        assert!(engine.terminal_lines.is_empty());
+
+        while !engine.is_finished() {
+            clock.advance_to(engine.next_deadline());
+            engine.tick();
+        }
+        assert_eq!(engine.terminal_lines, vec!["~ x"]);
+        // Two step delays plus the pause
+        assert_eq!(clock.elapsed(), Duration::from_millis(120));
+    }
+
+    #[test]
+    fn test_uncommitted_changes_hide_patch_id() {
+        let metadata = CommitMetadata {
+            hash: "f00dfacefeed".to_string(),
+            parent_hash: Some("0123456789ab".to_string()),
+            uncommitted: Some(crate::git::UncommittedSource::Worktree),
+            ..empty_commit()
+        };
+        let output: Vec<String> = StepBuilder::build(&metadata, 30)
+            .into_iter()
+            .filter_map(|step| match step {
+                AnimationStep::TerminalOutput { text } => Some(text),
+                _ => None,
+            })
+            .collect();
+
+        assert!(output.iter().all(|line| !line.contains("f00dfac")));
+        assert!(output.contains(&"📍 Location: working tree changes by Ghost".to_string()));
+        assert!(output.contains(&"   0123456..working tree ✨ SUCCESS".to_string()));
+    }
+
+    #[test]
+    fn test_seeded_typing_delays_repeat() {
+        let play = |seed| {
+            let clock = VirtualClock::new();
+            let mut engine = AnimationEngine::new(30, Rc::new(clock.clone()));
+            engine.set_seed(seed);
+            let metadata = empty_commit();
+            let mut steps = vec![AnimationStep::TerminalPrompt];
+            steps.extend(
+                "git push"
+                    .chars()
+                    .map(|ch| AnimationStep::TerminalTypeChar { ch }),
+            );
+            engine.load_steps(&metadata, steps, 30);
+
+            while !engine.is_finished() {
+                clock.advance_to(engine.next_deadline());
+                engine.tick();
+            }
+            clock.elapsed()
+        };
+        assert_eq!(play(7), play(7));
+    }
+}
@@ -0,0 +1,78 @@
+use std::cell::Cell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+/// Source of the current time for the animation engine
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Wall-clock time
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Time that only moves when advanced, so playback can run faster than real time
+/// Clones share the same time
+#[derive(Clone)]
+pub struct VirtualClock {
+    start: Instant,
+    now: Rc<Cell<Instant>>,
+}
+
+impl VirtualClock {
+    pub fn new() -> Self {
+        let start = Instant::now();
+        Self {
+            start,
+            now: Rc::new(Cell::new(start)),
+        }
+    }
+
+    /// Time passed since the clock was created
+    pub fn elapsed(&self) -> Duration {
+        self.now.get() - self.start
+    }
+
+    /// Move forward to `instant`; earlier instants leave the clock unchanged
+    pub fn advance_to(&self, instant: Instant) {
+        if instant > self.now.get() {
+            self.now.set(instant);
+        }
+    }
+}
+
+impl Default for VirtualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_virtual_clock() {
+        let clock = VirtualClock::new();
+        let shared = clock.clone();
+        let start = clock.now();
+
+        clock.advance_to(start + Duration::from_millis(250));
+        assert_eq!(shared.now() - start, Duration::from_millis(250));
+
+        shared.advance_to(start + Duration::from_secs(1));
+        clock.advance_to(start);
+        assert_eq!(clock.elapsed(), Duration::from_secs(1));
+    }
+}
@@ -1,7 +1,10 @@
 use anyhow::{Context, Result};
+use chrono::NaiveTime;
+use globset::GlobBuilder;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -17,6 +20,60 @@ pub struct Config {
     pub loop_playback: bool,
     #[serde(default = "default_ignore_patterns")]
     pub ignore_patterns: Vec<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+    #[serde(default)]
+    pub exclude_authors: Vec<String>,
+    #[serde(default)]
+    pub committers: Vec<String>,
+    #[serde(default)]
+    pub theme_day: Option<String>,
+    #[serde(default)]
+    pub theme_night: Option<String>,
+    #[serde(default = "default_day_starts")]
+    pub day_starts: String,
+    #[serde(default = "default_night_starts")]
+    pub night_starts: String,
+    #[serde(default)]
+    pub repo_themes: Vec<RepoTheme>,
+    /// Extensions, file names or globs mapped to language names
+    #[serde(default)]
+    pub languages: BTreeMap<String, String>,
+}
+
+/// Theme for repositories matching a path glob and/or a remote URL pattern
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoTheme {
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub remote: Option<String>,
+    pub theme: String,
+}
+
+/// Day and night themes and the local times at which they take over
+#[derive(Debug, Clone)]
+pub struct ThemeSchedule {
+    pub day: String,
+    pub night: String,
+    pub day_starts: NaiveTime,
+    pub night_starts: NaiveTime,
+}
+
+impl ThemeSchedule {
+    pub fn theme_at(&self, time: NaiveTime) -> &str {
+        let is_day = if self.day_starts <= self.night_starts {
+            time >= self.day_starts && time < self.night_starts
+        } else {
+            // Day wraps around midnight
+            time >= self.day_starts || time < self.night_starts
+        };
+        if is_day {
+            &self.day
+        } else {
+            &self.night
+        }
+    }
 }
 
 fn default_theme() -> String {
@@ -43,6 +100,14 @@ fn default_ignore_patterns() -> Vec<String> {
     Vec::new()
 }
 
+fn default_day_starts() -> String {
+    "07:00".to_string()
+}
+
+fn default_night_starts() -> String {
+    "19:00".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -52,6 +117,15 @@ impl Default for Config {
             order: default_order(),
             loop_playback: default_loop(),
             ignore_patterns: default_ignore_patterns(),
+            authors: Vec::new(),
+            exclude_authors: Vec::new(),
+            committers: Vec::new(),
+            theme_day: None,
+            theme_night: None,
+            day_starts: default_day_starts(),
+            night_starts: default_night_starts(),
+            repo_themes: Vec::new(),
+            languages: BTreeMap::new(),
         }
     }
 }
@@ -71,6 +145,38 @@ impl Config {
             .with_context(|| format!("Failed to parse config file: {}", config_path.display()))
     }
 
+    /// Theme of the first `repo_themes` rule matching the repository
+    pub fn repo_theme(&self, repo_path: &Path, remote_urls: &[String]) -> Result<Option<&str>> {
+        for rule in &self.repo_themes {
+            if rule.matches(repo_path, remote_urls)? {
+                return Ok(Some(&rule.theme));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Day and night themes, if either is set (the other one falls back to `theme`)
+    pub fn theme_schedule(&self) -> Result<Option<ThemeSchedule>> {
+        if self.theme_day.is_none() && self.theme_night.is_none() {
+            return Ok(None);
+        }
+
+        let time = |key: &str, value: &str| {
+            NaiveTime::parse_from_str(value, "%H:%M").with_context(|| {
+                format!("Invalid {} in config file: {} (expected HH:MM)", key, value)
+            })
+        };
+        Ok(Some(ThemeSchedule {
+            day: self.theme_day.clone().unwrap_or_else(|| self.theme.clone()),
+            night: self
+                .theme_night
+                .clone()
+                .unwrap_or_else(|| self.theme.clone()),
+            day_starts: time("day_starts", &self.day_starts)?,
+            night_starts: time("night_starts", &self.night_starts)?,
+        }))
+    }
+
     pub fn save(&self) -> Result<()> {
         let config_path = Self::config_path()?;
 
@@ -165,7 +271,6 @@ impl Config {
         Ok(config_dir.join("config.toml"))
     }
 
-    #[allow(dead_code)]
     pub fn themes_dir() -> Result<PathBuf> {
         let config_dir = dirs::home_dir()
             .context("Failed to determine home directory")?
@@ -183,3 +288,176 @@ impl Config {
         Ok(config_dir)
     }
 }
+
+impl RepoTheme {
+    /// Whether every pattern set in the rule matches the repository
+    fn matches(&self, repo_path: &Path, remote_urls: &[String]) -> Result<bool> {
+        if self.path.is_none() && self.remote.is_none() {
+            anyhow::bail!(
+                "repo_themes entry for theme '{}' needs a `path` or `remote` pattern",
+                self.theme
+            );
+        }
+
+        if let Some(pattern) = &self.path {
+            // ~/work/* and ~/work/*/ both match the repositories in ~/work
+            let pattern = match pattern.strip_prefix("~/") {
+                Some(rest) => dirs::home_dir()
+                    .context("Failed to determine home directory")?
+                    .join(rest)
+                    .to_string_lossy()
+                    .into_owned(),
+                None => pattern.clone(),
+            };
+            let glob = GlobBuilder::new(pattern.trim_end_matches('/'))
+                .literal_separator(true)
+                .build()
+                .with_context(|| format!("Invalid repo_themes path pattern: {}", pattern))?
+                .compile_matcher();
+            if !glob.is_match(repo_path) {
+                return Ok(false);
+            }
+        }
+
+        if let Some(pattern) = &self.remote {
+            let glob = GlobBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .with_context(|| format!("Invalid repo_themes remote pattern: {}", pattern))?
+                .compile_matcher();
+            let matched = remote_urls
+                .iter()
+                .any(|url| glob.is_match(url) || glob.is_match(normalize_remote_url(url)));
+            if !matched {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// `host/owner/repo` for both URL and scp-like remotes:
+/// `git@github.com:acme/app.git` and `https://github.com/acme/app` -> `github.com/acme/app`
+fn normalize_remote_url(url: &str) -> String {
+    let (rest, scp_like) = match url.split_once("://") {
+        Some((_, rest)) => (rest, false),
+        None => (url, true),
+    };
+    let host_end = rest.find('/').unwrap_or(rest.len());
+    let rest = match rest[..host_end].rfind('@') {
+        Some(at) => &rest[at + 1..],
+        None => rest,
+    };
+    let rest = if scp_like {
+        rest.replacen(':', "/", 1)
+    } else {
+        rest.to_string()
+    };
+    rest.trim_end_matches('/')
+        .trim_end_matches(".git")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(path: Option<&str>, remote: Option<&str>) -> RepoTheme {
+        RepoTheme {
+            path: path.map(String::from),
+            remote: remote.map(String::from),
+            theme: "nord".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_normalize_remote_url() {
+        for url in [
+            "git@github.com:acme/app.git",
+            "https://github.com/acme/app",
+            "https://user@github.com/acme/app.git/",
+            "ssh://git@github.com/acme/app.git",
+        ] {
+            assert_eq!(normalize_remote_url(url), "github.com/acme/app", "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_repo_theme_rules() {
+        let remotes = vec!["git@github.com:Acme/app.git".to_string()];
+        let repo = Path::new("/srv/work/app");
+
+        assert!(rule(Some("/srv/work/*"), None)
+            .matches(repo, &remotes)
+            .unwrap());
+        assert!(rule(Some("/srv/**/"), None)
+            .matches(repo, &remotes)
+            .unwrap());
+        assert!(!rule(Some("/srv/*"), None).matches(repo, &remotes).unwrap());
+        assert!(rule(None, Some("github.com/acme/*"))
+            .matches(repo, &remotes)
+            .unwrap());
+        assert!(rule(None, Some("*:Acme/app.git"))
+            .matches(repo, &remotes)
+            .unwrap());
+        assert!(!rule(Some("/srv/work/*"), Some("gitlab.com/*"))
+            .matches(repo, &remotes)
+            .unwrap());
+        assert!(rule(None, None).matches(repo, &remotes).is_err());
+
+        let config = Config {
+            repo_themes: vec![rule(Some("/tmp/*"), None), rule(Some("/srv/**"), None)],
+            ..Config::default()
+        };
+        assert_eq!(config.repo_theme(repo, &[]).unwrap(), Some("nord"));
+        assert_eq!(
+            config.repo_theme(Path::new("/home/app"), &[]).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_theme_schedule() {
+        let config = Config {
+            theme: "nord".to_string(),
+            theme_day: Some("solarized-light".to_string()),
+            ..Config::default()
+        };
+        let schedule = config.theme_schedule().unwrap().unwrap();
+        assert_eq!(schedule.night, "nord");
+        assert_eq!(
+            schedule.day_starts,
+            NaiveTime::from_hms_opt(7, 0, 0).unwrap()
+        );
+
+        assert!(Config::default().theme_schedule().unwrap().is_none());
+        let invalid = Config {
+            night_starts: "7pm".to_string(),
+            ..config
+        };
+        assert!(invalid.theme_schedule().is_err());
+    }
+
+    #[test]
+    fn test_schedule_theme_at() {
+        let time = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        let schedule = ThemeSchedule {
+            day: "solarized-light".to_string(),
+            night: "tokyo-night".to_string(),
+            day_starts: time(7),
+            night_starts: time(19),
+        };
+        assert_eq!(schedule.theme_at(time(6)), "tokyo-night");
+        assert_eq!(schedule.theme_at(time(7)), "solarized-light");
+        assert_eq!(schedule.theme_at(time(19)), "tokyo-night");
+
+        let wrapped = ThemeSchedule {
+            day_starts: time(22),
+            night_starts: time(6),
+            ..schedule
+        };
+        assert_eq!(wrapped.theme_at(time(23)), "solarized-light");
+        assert_eq!(wrapped.theme_at(time(12)), "tokyo-night");
+    }
+}
@@ -0,0 +1,545 @@
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use ratatui::buffer::Buffer;
+use ratatui::style::{Color, Modifier};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::Duration;
+use unicode_width::UnicodeWidthStr;
+
+use crate::git::CommitMetadata;
+use crate::record::{replay_headless, replay_title, RecordOptions};
+use crate::theme::{color_rgb, Theme};
+use crate::theme_switcher::ThemeSwitcher;
+
+// Cell size in pixels for a 14px monospace font
+const FONT_SIZE: f64 = 14.0;
+const CELL_WIDTH: f64 = 8.4;
+const CELL_HEIGHT: f64 = 17.0;
+const TEXT_BASELINE: f64 = 13.0;
+const FONT_FAMILY: &str =
+    "ui-monospace, SFMono-Regular, Menlo, Consolas, 'DejaVu Sans Mono', monospace";
+
+// Used where the theme leaves colors to the terminal (e.g. with --color-depth mono)
+const DEFAULT_FG: Rgb = (0xc0, 0xc0, 0xc0);
+const DEFAULT_BG: Rgb = (0x1e, 0x1e, 0x1e);
+
+type Rgb = (u8, u8, u8);
+
+/// File format of an exported replay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Animated SVG driven by CSS keyframes
+    Svg,
+    /// HTML page with a JavaScript player
+    Html,
+}
+
+/// Replay a commit off-screen and save it to `output` as an SVG animation or HTML player
+pub fn export(
+    metadata: CommitMetadata,
+    options: &RecordOptions,
+    format: ExportFormat,
+    themes: ThemeSwitcher,
+    output: &Path,
+) -> Result<()> {
+    let title = replay_title(&metadata);
+    let palette = Palette::new(&themes.load()?);
+    let mut film = Film::new(palette, options.width, options.height);
+    let duration = replay_headless(metadata, options, themes, |time, buffer| {
+        film.push(time, buffer);
+        Ok(())
+    })?;
+
+    let document = match format {
+        ExportFormat::Svg => film.svg(&title, duration),
+        ExportFormat::Html => film.html(&title, duration),
+    };
+    std::fs::write(output, document)
+        .with_context(|| format!("Failed to write {}", output.display()))?;
+
+    println!(
+        "Exported {} frames ({:.1}s) to {}",
+        film.frames.len(),
+        duration.as_secs_f64(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Colors that cells without their own color are drawn with
+struct Palette {
+    fg: Rgb,
+    bg: Rgb,
+}
+
+impl Palette {
+    fn new(theme: &Theme) -> Self {
+        Self {
+            fg: color_rgb(theme.file_tree_default).unwrap_or(DEFAULT_FG),
+            bg: color_rgb(theme.background_right).unwrap_or(DEFAULT_BG),
+        }
+    }
+
+    fn fg(&self, color: Color) -> Rgb {
+        color_rgb(color).unwrap_or(self.fg)
+    }
+
+    fn bg(&self, color: Color) -> Rgb {
+        color_rgb(color).unwrap_or(self.bg)
+    }
+}
+
+/// Cells of a row sharing one style
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Run {
+    col: u16,
+    /// Width in cells
+    width: u16,
+    text: String,
+    fg: Rgb,
+    bg: Rgb,
+    bold: bool,
+    italic: bool,
+    underline: bool,
+}
+
+impl Run {
+    fn same_style(&self, other: &Run) -> bool {
+        (self.fg, self.bg, self.bold, self.italic, self.underline)
+            == (
+                other.fg,
+                other.bg,
+                other.bold,
+                other.italic,
+                other.underline,
+            )
+    }
+}
+
+/// Frames of a replay with each distinct row stored once,
+/// as most rows stay the same from one frame to the next
+struct Film {
+    palette: Palette,
+    width: u16,
+    height: u16,
+    rows: Vec<Vec<Run>>,
+    row_ids: HashMap<Vec<Run>, usize>,
+    /// Time each frame appears at and the rows it shows, top to bottom
+    frames: Vec<(Duration, Vec<usize>)>,
+}
+
+impl Film {
+    fn new(palette: Palette, width: u16, height: u16) -> Self {
+        Self {
+            palette,
+            width,
+            height,
+            rows: Vec::new(),
+            row_ids: HashMap::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Add the frame shown from `time` on; frames identical to the last one are dropped
+    fn push(&mut self, time: Duration, buffer: &Buffer) {
+        let area = buffer.area;
+        let rows: Vec<usize> = (area.top()..area.bottom())
+            .map(|y| {
+                let runs = self.runs(buffer, y);
+                let next_id = self.rows.len();
+                let id = *self.row_ids.entry(runs.clone()).or_insert(next_id);
+                if id == next_id {
+                    self.rows.push(runs);
+                }
+                id
+            })
+            .collect();
+
+        if self.frames.last().is_some_and(|(_, last)| *last == rows) {
+            return;
+        }
+        self.frames.push((time, rows));
+    }
+
+    fn runs(&self, buffer: &Buffer, y: u16) -> Vec<Run> {
+        let area = buffer.area;
+        let mut runs: Vec<Run> = Vec::new();
+        let mut x = area.left();
+        while x < area.right() {
+            let cell = &buffer[(x, y)];
+            let symbol = cell.symbol();
+            // Wide characters cover the cells after them
+            let width = (symbol.width() as u16).max(1);
+
+            let (mut fg, mut bg) = (self.palette.fg(cell.fg), self.palette.bg(cell.bg));
+            if cell.modifier.contains(Modifier::REVERSED) {
+                std::mem::swap(&mut fg, &mut bg);
+            }
+            let run = Run {
+                col: x - area.left(),
+                width,
+                text: symbol.to_string(),
+                fg,
+                bg,
+                bold: cell.modifier.contains(Modifier::BOLD),
+                italic: cell.modifier.contains(Modifier::ITALIC),
+                underline: cell.modifier.contains(Modifier::UNDERLINED),
+            };
+
+            match runs.last_mut() {
+                Some(last) if last.same_style(&run) => {
+                    last.width += run.width;
+                    last.text.push_str(&run.text);
+                }
+                _ => runs.push(run),
+            }
+            x += width;
+        }
+        runs
+    }
+
+    /// Animated SVG driven by CSS keyframes
+    fn svg(&self, title: &str, duration: Duration) -> String {
+        let width = f64::from(self.width) * CELL_WIDTH;
+        let height = f64::from(self.height) * CELL_HEIGHT;
+        let mut styles = format!(
+            "text {{ font-family: {}; font-size: {}px; white-space: pre; }}\n",
+            FONT_FAMILY, FONT_SIZE
+        );
+        let mut svg = String::from("<defs>\n");
+        for (id, runs) in self.rows.iter().enumerate() {
+            let _ = write!(svg, r#"<g id="r{}">"#, id);
+            for run in runs.iter().filter(|run| run.bg != self.palette.bg) {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{:.1}" width="{:.1}" height="{}" fill="{}"/>"#,
+                    f64::from(run.col) * CELL_WIDTH,
+                    f64::from(run.width) * CELL_WIDTH,
+                    CELL_HEIGHT,
+                    hex(run.bg)
+                );
+            }
+            for run in runs.iter().filter(|run| !run.text.trim().is_empty()) {
+                // Trailing spaces would only stretch the text
+                let text = run.text.trim_end();
+                let width = run.width as usize - (run.text.len() - text.len());
+                let _ = write!(
+                    svg,
+                    r#"<text x="{:.1}" y="{}" textLength="{:.1}" fill="{}"{}>{}</text>"#,
+                    f64::from(run.col) * CELL_WIDTH,
+                    TEXT_BASELINE,
+                    width as f64 * CELL_WIDTH,
+                    hex(run.fg),
+                    svg_font_attributes(run),
+                    escape(text)
+                );
+            }
+            svg.push_str("</g>\n");
+        }
+        svg.push_str("</defs>\n");
+
+        // Each screen row is a strip of the rows it shows over time, side by side,
+        // which its own keyframes slide into view
+        for y in 0..self.height as usize {
+            let changes = self.row_changes(y);
+            let mut variants: Vec<usize> = Vec::new();
+            for (_, id) in &changes {
+                if !variants.contains(id) {
+                    variants.push(*id);
+                }
+            }
+
+            let _ = write!(
+                svg,
+                r#"<g class="y{}" transform="translate(0 {:.1})">"#,
+                y,
+                y as f64 * CELL_HEIGHT
+            );
+            for (index, id) in variants.iter().enumerate() {
+                let _ = write!(
+                    svg,
+                    "<use xlink:href=\"#r{}\" x=\"{:.1}\"/>",
+                    id,
+                    index as f64 * width
+                );
+            }
+            svg.push_str("</g>\n");
+
+            if variants.len() > 1 {
+                let offsets = changes.iter().map(|(time, id)| {
+                    let index = variants.iter().position(|variant| variant == id);
+                    (*time, index.unwrap_or(0) as f64 * width)
+                });
+                styles.push_str(&keyframes(y, offsets, duration));
+            }
+        }
+
+        format!(
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="{width:.1}" height="{height:.1}" viewBox="0 0 {width:.1} {height:.1}">"#,
+                "\n<title>{title}</title>\n<style>\n{styles}</style>\n",
+                r#"<rect width="100%" height="100%" fill="{background}"/>"#,
+                "\n{svg}</svg>\n"
+            ),
+            width = width,
+            height = height,
+            title = escape(title),
+            styles = styles,
+            background = hex(self.palette.bg),
+            svg = svg
+        )
+    }
+
+    /// When screen row `y` changes and to which row, starting with the first frame
+    fn row_changes(&self, y: usize) -> Vec<(Duration, usize)> {
+        let mut changes: Vec<(Duration, usize)> = Vec::new();
+        for (time, rows) in &self.frames {
+            if changes.last().is_none_or(|(_, id)| *id != rows[y]) {
+                changes.push((*time, rows[y]));
+            }
+        }
+        changes
+    }
+
+    /// HTML page that plays the frames with a small JavaScript player
+    fn html(&self, title: &str, duration: Duration) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|runs| {
+                runs.iter()
+                    .map(|run| {
+                        format!(
+                            r#"<span style="{}">{}</span>"#,
+                            html_style(run, &self.palette),
+                            escape(&run.text)
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        // Frames list only the rows that changed, as [y, row] pairs
+        let mut previous: Option<&Vec<usize>> = None;
+        let mut changes = Vec::with_capacity(self.frames.len());
+        for (time, rows) in &self.frames {
+            let changed: Vec<(usize, usize)> = rows
+                .iter()
+                .enumerate()
+                .filter(|&(y, id)| previous.is_none_or(|previous| previous[y] != *id))
+                .map(|(y, id)| (y, *id))
+                .collect();
+            changes.push(((time.as_secs_f64() * 1000.0).round() / 1000.0, changed));
+            previous = Some(rows);
+        }
+
+        // Keep "</script>" in the data from closing the script element
+        let json = |value: serde_json::Value| value.to_string().replace("</", "<\\/");
+        fill(
+            HTML_TEMPLATE,
+            &[
+                ("title", &escape(title)),
+                ("fg", &hex(self.palette.fg)),
+                ("bg", &hex(self.palette.bg)),
+                ("font", FONT_FAMILY),
+                ("rows", &json(serde_json::json!(rows))),
+                ("changes", &json(serde_json::json!(changes))),
+                ("duration", &format!("{:.3}", duration.as_secs_f64())),
+            ],
+        )
+    }
+}
+
+/// Keyframes sliding the strip of screen row `y` by `offsets` (in pixels) at the given times,
+/// each holding until the next
+fn keyframes(
+    y: usize,
+    offsets: impl Iterator<Item = (Duration, f64)>,
+    duration: Duration,
+) -> String {
+    let total = duration.as_secs_f64().max(f64::EPSILON);
+    let mut css = format!("@keyframes y{} {{", y);
+    for (time, offset) in offsets {
+        let _ = write!(
+            css,
+            " {:.4}% {{ transform: translate({:.1}px, {:.1}px); }}",
+            time.as_secs_f64() / total * 100.0,
+            -offset,
+            y as f64 * CELL_HEIGHT
+        );
+    }
+    let _ = writeln!(
+        css,
+        " }}\n.y{} {{ animation: y{} {:.3}s steps(1, end) infinite; }}",
+        y, y, total
+    );
+    css
+}
+
+/// Replace `{{name}}` placeholders in one pass, so inserted text is never expanded again
+fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(end) = rest[start..].find("}}") else {
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+        filled.push_str(&rest[..start]);
+        match values.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => filled.push_str(value),
+            None => filled.push_str(&rest[start..start + end + 2]),
+        }
+        rest = &rest[start + end + 2..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
+fn svg_font_attributes(run: &Run) -> String {
+    let mut attributes = String::new();
+    if run.bold {
+        attributes.push_str(r#" font-weight="bold""#);
+    }
+    if run.italic {
+        attributes.push_str(r#" font-style="italic""#);
+    }
+    if run.underline {
+        attributes.push_str(r#" text-decoration="underline""#);
+    }
+    attributes
+}
+
+fn html_style(run: &Run, palette: &Palette) -> String {
+    let mut style = format!("color:{}", hex(run.fg));
+    if run.bg != palette.bg {
+        let _ = write!(style, ";background:{}", hex(run.bg));
+    }
+    if run.bold {
+        style.push_str(";font-weight:bold");
+    }
+    if run.italic {
+        style.push_str(";font-style:italic");
+    }
+    if run.underline {
+        style.push_str(";text-decoration:underline");
+    }
+    style
+}
+
+fn hex((r, g, b): Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Escape text for XML and HTML
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+const HTML_TEMPLATE: &str = include_str!("player.html");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    fn film() -> Film {
+        Film::new(
+            Palette {
+                fg: (0xaa, 0xaa, 0xaa),
+                bg: (0, 0, 0),
+            },
+            6,
+            2,
+        )
+    }
+
+    #[test]
+    fn test_film_deduplicates_rows_and_frames() {
+        let mut film = film();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "fn", Style::default().fg(Color::Rgb(255, 0, 0)));
+        film.push(Duration::ZERO, &buffer);
+        film.push(Duration::from_millis(10), &buffer);
+
+        buffer.set_string(0, 1, "x<y", Style::default());
+        film.push(Duration::from_millis(20), &buffer);
+
+        // Rows "fn", "x<y" and a blank one; the repeated frame is dropped
+        assert_eq!(film.rows.len(), 3);
+        assert_eq!(film.frames.len(), 2);
+        assert_eq!(film.frames[0].1[0], film.frames[1].1[0]);
+        assert_eq!(
+            film.rows[film.frames[0].1[0]],
+            vec![
+                Run {
+                    col: 0,
+                    width: 2,
+                    text: "fn".to_string(),
+                    fg: (255, 0, 0),
+                    bg: (0, 0, 0),
+                    bold: false,
+                    italic: false,
+                    underline: false,
+                },
+                Run {
+                    col: 2,
+                    width: 4,
+                    text: "    ".to_string(),
+                    fg: (0xaa, 0xaa, 0xaa),
+                    bg: (0, 0, 0),
+                    bold: false,
+                    italic: false,
+                    underline: false,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_wide_characters_span_cells() {
+        let mut film = film();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "🚀a", Style::default().bg(Color::Indexed(4)));
+        film.push(Duration::ZERO, &buffer);
+
+        let runs = &film.rows[0];
+        assert_eq!(runs[0].text, "🚀a");
+        assert_eq!(runs[0].width, 3);
+        assert_eq!(runs[1].col, 3);
+    }
+
+    #[test]
+    fn test_svg_and_html_output() {
+        let mut film = film();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        film.push(Duration::ZERO, &buffer);
+        buffer.set_string(0, 0, "</a>", Style::default());
+        film.push(Duration::from_secs(1), &buffer);
+
+        let svg = film.svg("demo & co", Duration::from_secs(2));
+        assert!(svg.contains("<title>demo &amp; co</title>"));
+        // Only the first row changes; it slides to its second variant halfway through
+        assert!(svg.contains("@keyframes y0 { 0.0000% { transform: translate(-0.0px, 0.0px); } 50.0000% { transform: translate(-50.4px, 0.0px); } }"));
+        assert!(svg.contains(".y0 { animation: y0 2.000s steps(1, end) infinite; }"));
+        assert!(!svg.contains("@keyframes y1"));
+        assert!(svg.contains(">&lt;/a&gt;</text>"));
+
+        let html = film.html("demo", Duration::from_secs(2));
+        assert!(html.contains("const DURATION = 2.000;"));
+        assert!(html.contains("const CHANGES = [[0.0,[[0,0],[1,0]]],[1.0,[[0,1]]]];"));
+        assert!(!html.contains("</a>"));
+    }
+}
@@ -0,0 +1,111 @@
+use git2::{Repository, Tree};
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::Path;
+
+const LINGUIST_LANGUAGE: &str = "linguist-language";
+
+/// Language override for `path` from the `.gitattributes` files in `tree`
+///
+/// Reads the files of the commit being replayed, as `Repository::get_attr`
+/// only sees the working tree and index.
+pub fn tree_linguist_language(repo: &Repository, tree: &Tree, path: &str) -> Option<String> {
+    let mut language = None;
+    // Files closer to `path` take precedence, so read them last
+    for dir in ancestors(path) {
+        let attributes_path = if dir.is_empty() {
+            ".gitattributes".to_string()
+        } else {
+            format!("{}/.gitattributes", dir)
+        };
+        let Some(content) = tree
+            .get_path(Path::new(&attributes_path))
+            .ok()
+            .and_then(|entry| repo.find_blob(entry.id()).ok())
+            .map(|blob| String::from_utf8_lossy(blob.content()).to_string())
+        else {
+            continue;
+        };
+        let relative = path[dir.len()..].trim_start_matches('/');
+        if let Some(value) = file_linguist_language(&content, relative) {
+            language = value;
+        }
+    }
+    language
+}
+
+/// Directories containing `path`, from the root down; the root is `""`
+fn ancestors(path: &str) -> impl Iterator<Item = &str> {
+    std::iter::once("").chain(
+        path.match_indices('/')
+            .map(move |(index, _)| &path[..index]),
+    )
+}
+
+/// The `linguist-language` state that the last matching line of a
+/// `.gitattributes` file gives `path`: `Some(None)` when it is unset
+fn file_linguist_language(content: &str, path: &str) -> Option<Option<String>> {
+    let mut language = None;
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next().filter(|pattern| !pattern.starts_with('#')) else {
+            continue;
+        };
+        let Some(value) = fields.filter_map(linguist_value).next_back() else {
+            continue;
+        };
+        if matches(pattern, path) {
+            language = Some(value);
+        }
+    }
+    language
+}
+
+/// Value of a `linguist-language` attribute token, `None` when it is unset
+fn linguist_value(token: &str) -> Option<Option<String>> {
+    if let Some(value) = token
+        .strip_prefix(LINGUIST_LANGUAGE)
+        .and_then(|rest| rest.strip_prefix('='))
+    {
+        return Some(Some(value.to_string()));
+    }
+    match token.strip_prefix(['-', '!']) {
+        Some(LINGUIST_LANGUAGE) => Some(None),
+        _ => None,
+    }
+}
+
+/// Whether a `.gitattributes` pattern matches `path`, relative to the file's directory
+///
+/// Patterns without a `/` match the file name at any depth, others the whole path.
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, subject) = match pattern.strip_prefix('/') {
+        Some(anchored) => (anchored, path),
+        None if !pattern.contains('/') => (pattern, path.rsplit('/').next().unwrap_or(path)),
+        None => (pattern, path),
+    };
+    GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .map(|glob| glob.compile_matcher())
+        .is_ok_and(|matcher: GlobMatcher| matcher.is_match(subject))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_linguist_language() {
+        let content = "# Overrides\n\
+                       *.inc linguist-language=PHP\n\
+                       vendor/*.inc -linguist-language\n\
+                       /build.tmpl linguist-generated linguist-language=Go\n";
+
+        let language = |path| file_linguist_language(content, path);
+        assert_eq!(language("lib/db.inc"), Some(Some("PHP".to_string())));
+        assert_eq!(language("vendor/db.inc"), Some(None));
+        assert_eq!(language("build.tmpl"), Some(Some("Go".to_string())));
+        assert_eq!(language("lib/build.tmpl"), None);
+        assert_eq!(language("src/main.rs"), None);
+    }
+}
@@ -0,0 +1,367 @@
+use super::window::{DateField, DateWindow};
+use anyhow::{Context, Result};
+use chrono::DateTime;
+use git2::{Commit, Mailmap, Repository, Signature};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use regex::{Regex, RegexBuilder};
+
+/// A case-insensitive pattern matched against `Name <email>` identities
+///
+/// Patterns are regular expressions; anything that does not compile as one
+/// is matched as a plain substring instead.
+#[derive(Debug, Clone)]
+pub struct IdentityPattern(Regex);
+
+impl IdentityPattern {
+    pub fn new(pattern: &str) -> Result<Self> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .build()
+            .or_else(|_| {
+                RegexBuilder::new(&regex::escape(pattern))
+                    .case_insensitive(true)
+                    .build()
+            })
+            .with_context(|| format!("Invalid identity pattern: {}", pattern))?;
+        Ok(Self(regex))
+    }
+
+    pub fn is_match(&self, identity: &str) -> bool {
+        self.0.is_match(identity)
+    }
+}
+
+/// Decides which commits are candidates for playback
+#[derive(Debug, Clone, Default)]
+pub struct CommitFilter {
+    authors: Vec<IdentityPattern>,
+    excluded_authors: Vec<IdentityPattern>,
+    committers: Vec<IdentityPattern>,
+    window: DateWindow,
+    paths: Option<GlobSet>,
+    grep: Vec<Regex>,
+    invert_grep: bool,
+    include_merges: bool,
+    first_parent: bool,
+}
+
+impl CommitFilter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep commits whose author matches one of `patterns`
+    pub fn with_authors(mut self, patterns: &[String]) -> Result<Self> {
+        self.authors = compile_patterns(patterns)?;
+        Ok(self)
+    }
+
+    /// Drop commits whose author matches one of `patterns`
+    pub fn with_excluded_authors(mut self, patterns: &[String]) -> Result<Self> {
+        self.excluded_authors = compile_patterns(patterns)?;
+        Ok(self)
+    }
+
+    /// Only keep commits whose committer matches one of `patterns`
+    pub fn with_committers(mut self, patterns: &[String]) -> Result<Self> {
+        self.committers = compile_patterns(patterns)?;
+        Ok(self)
+    }
+
+    /// Only keep commits whose author or committer time falls inside `window`
+    pub fn with_window(mut self, window: DateWindow) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Only keep commits whose message matches one of `patterns`,
+    /// or none of them when `invert` is set
+    pub fn with_grep(mut self, patterns: &[String], invert: bool) -> Result<Self> {
+        self.grep = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).with_context(|| format!("Invalid grep pattern: {}", pattern))
+            })
+            .collect::<Result<_>>()?;
+        self.invert_grep = invert;
+        Ok(self)
+    }
+
+    /// Also play merge commits (as their diff against the first parent)
+    pub fn with_merges(mut self, include: bool) -> Self {
+        self.include_merges = include;
+        self
+    }
+
+    /// Follow only the first parent of merges when walking history
+    pub fn with_first_parent(mut self, first_parent: bool) -> Self {
+        self.first_parent = first_parent;
+        self
+    }
+
+    pub fn include_merges(&self) -> bool {
+        self.include_merges
+    }
+
+    pub fn first_parent(&self) -> bool {
+        self.first_parent
+    }
+
+    /// Only keep commits touching one of `specs` (globs or directory prefixes)
+    pub fn with_paths(mut self, specs: &[String]) -> Result<Self> {
+        self.paths = compile_pathspecs(specs)?;
+        Ok(self)
+    }
+
+    /// Whether `path` is covered by the pathspecs (always true without any)
+    pub fn path_in_scope(&self, path: &str) -> bool {
+        self.paths.as_ref().is_none_or(|paths| paths.is_match(path))
+    }
+
+    pub fn window(&self) -> &DateWindow {
+        &self.window
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.authors.is_empty()
+            && self.excluded_authors.is_empty()
+            && self.committers.is_empty()
+            && self.window.is_empty()
+            && self.paths.is_none()
+            && self.grep.is_empty()
+    }
+
+    /// Whether matching needs identities resolved through `.mailmap`
+    pub fn uses_identities(&self) -> bool {
+        !(self.authors.is_empty() && self.excluded_authors.is_empty() && self.committers.is_empty())
+    }
+
+    /// Check a commit against the filter, resolving identities through `.mailmap`
+    pub fn matches(&self, repo: &Repository, commit: &Commit, mailmap: Option<&Mailmap>) -> bool {
+        if !self.window.is_empty() {
+            let time = match self.window.field {
+                DateField::Author => commit.author().when(),
+                DateField::Committer => commit.committer().when(),
+            };
+            match DateTime::from_timestamp(time.seconds(), 0) {
+                Some(time) if self.window.contains(time) => {}
+                _ => return false,
+            }
+        }
+
+        if !self.grep.is_empty() && !self.message_matches(commit.message().unwrap_or("")) {
+            return false;
+        }
+
+        if self.uses_identities() {
+            let authors = identities(&commit.author(), mailmap);
+            let committers = identities(&commit.committer(), mailmap);
+            if !self.accepts(&authors, &committers) {
+                return false;
+            }
+        }
+
+        // Diffing is the most expensive check, so it runs last
+        self.paths.is_none() || self.touches_scope(repo, commit)
+    }
+
+    fn message_matches(&self, message: &str) -> bool {
+        self.grep.iter().any(|regex| regex.is_match(message)) != self.invert_grep
+    }
+
+    fn touches_scope(&self, repo: &Repository, commit: &Commit) -> bool {
+        let Ok(tree) = commit.tree() else {
+            return false;
+        };
+        let parent_tree = commit.parent(0).and_then(|parent| parent.tree()).ok();
+        let Ok(diff) = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None) else {
+            return false;
+        };
+
+        diff.deltas().any(|delta| {
+            [delta.new_file().path(), delta.old_file().path()]
+                .into_iter()
+                .flatten()
+                .filter_map(|path| path.to_str())
+                .any(|path| self.path_in_scope(path))
+        })
+    }
+
+    fn accepts(&self, authors: &[String], committers: &[String]) -> bool {
+        if !self.authors.is_empty() && !any_match(&self.authors, authors) {
+            return false;
+        }
+        if any_match(&self.excluded_authors, authors) {
+            return false;
+        }
+        if !self.committers.is_empty() && !any_match(&self.committers, committers) {
+            return false;
+        }
+        true
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<IdentityPattern>> {
+    patterns.iter().map(|p| IdentityPattern::new(p)).collect()
+}
+
+/// Each spec matches as a glob and, like a git pathspec, as a directory prefix
+fn compile_pathspecs(specs: &[String]) -> Result<Option<GlobSet>> {
+    if specs.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for spec in specs {
+        let spec = spec.trim_start_matches("./").trim_end_matches('/');
+        for pattern in [spec.to_string(), format!("{}/**", spec)] {
+            let glob = GlobBuilder::new(&pattern)
+                .literal_separator(true)
+                .build()
+                .with_context(|| format!("Invalid path pattern: {}", spec))?;
+            builder.add(glob);
+        }
+    }
+
+    builder
+        .build()
+        .map(Some)
+        .context("Failed to build path patterns")
+}
+
+fn any_match(patterns: &[IdentityPattern], identities: &[String]) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| identities.iter().any(|identity| pattern.is_match(identity)))
+}
+
+/// The recorded identity followed by its mailmap alias, if it differs
+fn identities(signature: &Signature, mailmap: Option<&Mailmap>) -> Vec<String> {
+    let mut result = vec![format_identity(signature)];
+    if let Some(mapped) = mailmap.and_then(|mailmap| mailmap.resolve_signature(signature).ok()) {
+        let mapped = format_identity(&mapped);
+        if mapped != result[0] {
+            result.push(mapped);
+        }
+    }
+    result
+}
+
+fn format_identity(signature: &Signature) -> String {
+    format!(
+        "{} <{}>",
+        signature.name().unwrap_or(""),
+        signature.email().unwrap_or("")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(values: &[&str]) -> Vec<String> {
+        values.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        patterns(values)
+    }
+
+    #[test]
+    fn test_identity_pattern_regex_and_substring() {
+        let regex = IdentityPattern::new("^alice").unwrap();
+        assert!(regex.is_match("Alice Smith <alice@example.com>"));
+        assert!(!regex.is_match("Bob <alice@example.com>"));
+
+        let substring = IdentityPattern::new("bob@").unwrap();
+        assert!(substring.is_match("Bob <BOB@example.com>"));
+
+        // Invalid regex falls back to a literal substring
+        let literal = IdentityPattern::new("c++ (dev").unwrap();
+        assert!(literal.is_match("C++ (Dev) <cpp@example.com>"));
+    }
+
+    #[test]
+    fn test_author_include_and_exclude() {
+        let filter = CommitFilter::new()
+            .with_authors(&patterns(&["alice@", "bob@"]))
+            .unwrap()
+            .with_excluded_authors(&patterns(&["bot"]))
+            .unwrap();
+
+        let committer = ids(&["GitHub <noreply@github.com>"]);
+        assert!(filter.accepts(&ids(&["Alice <alice@example.com>"]), &committer));
+        assert!(filter.accepts(&ids(&["Bob <bob@example.com>"]), &committer));
+        assert!(!filter.accepts(&ids(&["Carol <carol@example.com>"]), &committer));
+        assert!(!filter.accepts(&ids(&["alice-bot <alice@example.com>"]), &committer));
+    }
+
+    #[test]
+    fn test_committer_filter() {
+        let filter = CommitFilter::new()
+            .with_committers(&patterns(&["alice"]))
+            .unwrap();
+        let author = ids(&["Bob <bob@example.com>"]);
+        assert!(filter.accepts(&author, &ids(&["Alice <alice@example.com>"])));
+        assert!(!filter.accepts(&author, &ids(&["Bob <bob@example.com>"])));
+    }
+
+    #[test]
+    fn test_grep() {
+        let filter = CommitFilter::new()
+            .with_grep(&patterns(&["^feat:", "PAY-\\d+"]), false)
+            .unwrap();
+        assert!(filter.message_matches("feat: add refunds"));
+        assert!(filter.message_matches("fix: rounding\n\nRefs PAY-123"));
+        assert!(!filter.message_matches("chore: bump deps"));
+
+        let inverted = CommitFilter::new()
+            .with_grep(&patterns(&["^chore"]), true)
+            .unwrap();
+        assert!(inverted.message_matches("feat: add refunds"));
+        assert!(!inverted.message_matches("chore: bump deps"));
+
+        assert!(CommitFilter::new()
+            .with_grep(&patterns(&["(unclosed"]), false)
+            .is_err());
+    }
+
+    #[test]
+    fn test_pathspecs() {
+        let filter = CommitFilter::new()
+            .with_paths(&patterns(&["services/payments/**", "docs/", "*.toml"]))
+            .unwrap();
+        assert!(filter.path_in_scope("services/payments/src/lib.rs"));
+        assert!(filter.path_in_scope("docs/usage.md"));
+        assert!(filter.path_in_scope("Cargo.toml"));
+        assert!(!filter.path_in_scope("services/billing/src/lib.rs"));
+        assert!(!filter.path_in_scope("docs-old/usage.md"));
+        assert!(!filter.path_in_scope("services/payments.toml/x.rs"));
+
+        assert!(CommitFilter::new().path_in_scope("anything.rs"));
+        assert!(CommitFilter::new()
+            .with_paths(&patterns(&["[bad"]))
+            .is_err());
+    }
+
+    #[test]
+    fn test_mailmap_aliases_match() {
+        let mailmap =
+            Mailmap::from_buffer("Alice <alice@example.com> <alice@old-laptop.local>\n").unwrap();
+        let signature = Signature::now("alice", "alice@old-laptop.local").unwrap();
+
+        let identities = identities(&signature, Some(&mailmap));
+        assert_eq!(
+            identities,
+            vec![
+                "alice <alice@old-laptop.local>".to_string(),
+                "Alice <alice@example.com>".to_string()
+            ]
+        );
+
+        let filter = CommitFilter::new()
+            .with_authors(&patterns(&["alice@example.com"]))
+            .unwrap();
+        assert!(filter.accepts(&identities, &[]));
+    }
+}
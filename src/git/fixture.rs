@@ -0,0 +1,70 @@
+use git2::{Repository, RepositoryInitOptions, Signature, Time};
+use std::path::{Path, PathBuf};
+
+use super::{CommitMetadata, GitRepository};
+
+/// Git repository in a temp dir for tests, removed when dropped
+pub struct Fixture {
+    pub dir: PathBuf,
+    repo: Repository,
+}
+
+impl Fixture {
+    /// Create an empty repository; `name` keeps tests running in parallel apart
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("gitlogue-{}-{}", name, std::process::id()));
+        // Left over by an earlier run that was killed
+        let _ = std::fs::remove_dir_all(&dir);
+        // Fixed branch name, whatever init.defaultBranch says
+        let repo =
+            Repository::init_opts(&dir, RepositoryInitOptions::new().initial_head("main")).unwrap();
+        Self { dir, repo }
+    }
+
+    /// Write `files` and commit them on HEAD; a fixed date keeps hashes stable
+    pub fn commit(&self, message: &str, files: &[(&str, &str)]) -> String {
+        let mut index = self.repo.index().unwrap();
+        for (path, content) in files {
+            let full = self.dir.join(path);
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, content).unwrap();
+            index.add_path(Path::new(path)).unwrap();
+        }
+        index.write().unwrap();
+        let tree = self.repo.find_tree(index.write_tree().unwrap()).unwrap();
+
+        let signature =
+            Signature::new("Ghost", "ghost@example.com", &Time::new(1_700_000_000, 0)).unwrap();
+        let parent = self
+            .repo
+            .head()
+            .ok()
+            .map(|head| head.peel_to_commit().unwrap());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        self.repo
+            .commit(
+                Some("HEAD"),
+                &signature,
+                &signature,
+                message,
+                &tree,
+                &parents,
+            )
+            .unwrap()
+            .to_string()
+    }
+
+    pub fn open(&self) -> GitRepository {
+        GitRepository::open(&self.dir).unwrap()
+    }
+
+    pub fn metadata(&self, hash: &str) -> CommitMetadata {
+        self.open().get_commit(hash).unwrap()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,1302 @@
+mod attributes;
+mod filter;
+#[cfg(test)]
+pub mod fixture;
+mod refs;
+mod window;
+
+pub use filter::CommitFilter;
+pub use refs::{RefSelection, RefTip};
+pub use window::{DateField, DateWindow};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use git2::{
+    AttrCheckFlags, AttrValue, Commit as Git2Commit, Delta, Diff, DiffOptions, Index, Oid,
+    Repository, Revwalk, Sort, Tree,
+};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use regex::Regex;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Thread-safe global pattern matcher for user-defined ignore patterns
+static USER_PATTERNS: OnceLock<GlobSet> = OnceLock::new();
+
+// Maximum blob size to read (500KB)
+const MAX_BLOB_SIZE: usize = 500 * 1024;
+
+// Maximum number of changed lines per file to animate
+// Files with more changes will be skipped to prevent performance issues
+const MAX_CHANGE_LINES: usize = 2000;
+
+// Files to exclude from diff animation (lock files and generated files)
+const EXCLUDED_FILES: &[&str] = &[
+    // JavaScript/Node.js
+    "yarn.lock",
+    "package-lock.json",
+    "pnpm-lock.yaml",
+    "bun.lock",
+    "bun.lockb",
+    // Rust
+    "Cargo.lock",
+    // Ruby
+    "Gemfile.lock",
+    // Python
+    "poetry.lock",
+    "Pipfile.lock",
+    // PHP
+    "composer.lock",
+    // Go
+    "go.sum",
+    // Swift
+    "Package.resolved",
+    // Dart/Flutter
+    "pubspec.lock",
+    // .NET/C#
+    "packages.lock.json",
+    "project.assets.json",
+    // Elixir
+    "mix.lock",
+    // Java/Gradle
+    "gradle.lockfile",
+    "buildscript-gradle.lockfile",
+    // Scala
+    "build.sbt.lock",
+    // Bazel
+    "MODULE.bazel.lock",
+];
+
+// File patterns to exclude from diff animation
+const EXCLUDED_PATTERNS: &[&str] = &[
+    // Minified files
+    ".min.js",
+    ".min.css",
+    // Bundled files
+    ".bundle.js",
+    ".bundle.css",
+    // Source maps
+    ".js.map",
+    ".css.map",
+    ".d.ts.map",
+    // Test snapshots
+    ".snap",
+    "__snapshots__",
+];
+
+/// Initialize user-defined ignore patterns (call once at startup)
+pub fn init_ignore_patterns(patterns: &[String]) -> Result<()> {
+    if patterns.is_empty() {
+        return Ok(());
+    }
+
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let glob =
+            Glob::new(pattern).with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+        builder.add(glob);
+    }
+
+    let globset = builder.build().context("Failed to build glob set")?;
+
+    USER_PATTERNS
+        .set(globset)
+        .map_err(|_| anyhow::anyhow!("User patterns already initialized"))?;
+
+    Ok(())
+}
+
+/// Check if a file should be excluded from diff animation
+pub fn should_exclude_file(path: &str) -> bool {
+    // Check user-defined patterns first
+    if let Some(patterns) = USER_PATTERNS.get() {
+        if patterns.is_match(path) {
+            return true;
+        }
+    }
+
+    let filename = path.rsplit('/').next().unwrap_or(path);
+
+    // Check if it's a lock file
+    if EXCLUDED_FILES.contains(&filename) {
+        return true;
+    }
+
+    // Check if it matches excluded patterns
+    for pattern in EXCLUDED_PATTERNS {
+        if filename.ends_with(pattern) || path.contains(pattern) {
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Owns its `Repository` handle so it can be moved to a background worker thread
+pub struct GitRepository {
+    repo: Repository,
+    commit_cache: Option<Vec<Oid>>,
+    // Shared index for both cache-based playback (asc/desc) and range playback.
+    // These modes are mutually exclusive based on CLI arguments.
+    commit_index: usize,
+    commit_range: Option<Vec<Oid>>,
+    filter: CommitFilter,
+    refs: RefSelection,
+    /// Tips of the refs being played, resolved when the cache is built
+    tips: Vec<RefTip>,
+    /// Branch label per candidate commit when playing several refs
+    commit_labels: HashMap<Oid, String>,
+    /// Tips seen by the last `poll_new_commits` call
+    followed_tips: Option<Vec<RefTip>>,
+    /// Picks random commits; seeded for reproducible playback
+    rng: ChaCha8Rng,
+}
+
+#[derive(Debug, Clone)]
+pub enum FileStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+    Unmodified,
+}
+
+impl FileStatus {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FileStatus::Added => "A",
+            FileStatus::Deleted => "D",
+            FileStatus::Modified => "M",
+            FileStatus::Renamed => "R",
+            FileStatus::Copied => "C",
+            FileStatus::Unmodified => "U",
+        }
+    }
+}
+
+impl From<Delta> for FileStatus {
+    fn from(delta: Delta) -> Self {
+        match delta {
+            Delta::Added => FileStatus::Added,
+            Delta::Deleted => FileStatus::Deleted,
+            Delta::Modified => FileStatus::Modified,
+            Delta::Renamed => FileStatus::Renamed,
+            Delta::Copied => FileStatus::Copied,
+            Delta::Unmodified => FileStatus::Unmodified,
+            _ => FileStatus::Modified,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum LineChangeType {
+    Addition,
+    Deletion,
+    Context,
+}
+
+#[derive(Debug, Clone)]
+pub struct LineChange {
+    pub change_type: LineChangeType,
+    pub content: String,
+    #[allow(dead_code)]
+    pub old_line_no: Option<usize>,
+    #[allow(dead_code)]
+    pub new_line_no: Option<usize>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    #[allow(dead_code)]
+    pub old_lines: usize,
+    #[allow(dead_code)]
+    pub new_start: usize,
+    #[allow(dead_code)]
+    pub new_lines: usize,
+    pub lines: Vec<LineChange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FileChange {
+    pub path: String,
+    #[allow(dead_code)]
+    pub old_path: Option<String>,
+    pub status: FileStatus,
+    #[allow(dead_code)]
+    pub is_binary: bool,
+    pub is_excluded: bool,
+    /// Outside the `--path` pathspecs: shown in the file tree but not animated
+    pub is_out_of_scope: bool,
+    pub exclusion_reason: Option<String>,
+    pub old_content: Option<String>,
+    #[allow(dead_code)]
+    pub new_content: Option<String>,
+    pub hunks: Vec<DiffHunk>,
+    #[allow(dead_code)]
+    pub diff: String,
+    /// `linguist-language` set for the file in `.gitattributes`
+    pub language: Option<String>,
+}
+
+/// Which uncommitted changes are replayed as a synthetic commit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UncommittedSource {
+    /// Staged and unstaged changes, including untracked files
+    Worktree,
+    /// Changes in the index only
+    Staged,
+}
+
+impl UncommittedSource {
+    pub fn label(&self) -> &'static str {
+        match self {
+            UncommittedSource::Worktree => "working tree",
+            UncommittedSource::Staged => "staged",
+        }
+    }
+}
+
+/// Details of a merge commit, replayed as one diff against its first parent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeInfo {
+    /// Merged branch, parsed from the merge message
+    pub branch: Option<String>,
+    /// Pull request number, parsed from the merge message
+    pub pull_request: Option<u64>,
+    pub parent_count: usize,
+}
+
+impl MergeInfo {
+    /// Parse the merged branch from the usual GitHub, GitLab, Bitbucket and `git merge` messages
+    pub fn from_message(message: &str, parent_count: usize) -> Self {
+        static PATTERNS: OnceLock<[Regex; 3]> = OnceLock::new();
+        let patterns = PATTERNS.get_or_init(|| {
+            [
+                Regex::new(r"^Merge pull request #(?<pr>\d+) from (?<branch>\S+)").unwrap(),
+                Regex::new(r"^Merged in (?<branch>\S+) \(pull request #(?<pr>\d+)\)").unwrap(),
+                Regex::new(r"^Merge (?:remote-tracking )?branch '(?<branch>[^']+)'").unwrap(),
+            ]
+        });
+
+        let summary = message.lines().next().unwrap_or("");
+        let captures = patterns.iter().find_map(|regex| regex.captures(summary));
+
+        Self {
+            branch: captures
+                .as_ref()
+                .and_then(|caps| caps.name("branch"))
+                .map(|m| m.as_str().to_string()),
+            pull_request: captures
+                .as_ref()
+                .and_then(|caps| caps.name("pr"))
+                .and_then(|m| m.as_str().parse().ok()),
+            parent_count,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CommitMetadata {
+    pub hash: String,
+    /// First parent; for uncommitted changes the HEAD commit, unset when there is none
+    pub parent_hash: Option<String>,
+    pub author: String,
+    pub date: DateTime<Utc>,
+    pub message: String,
+    pub changes: Vec<FileChange>,
+    /// Set for merge commits
+    pub merge: Option<MergeInfo>,
+    /// Branch (or tag) the commit is played from
+    pub ref_name: Option<String>,
+    /// Set for synthetic commits built from uncommitted changes
+    pub uncommitted: Option<UncommittedSource>,
+}
+
+impl CommitMetadata {
+    /// Abbreviated hash as shown on screen
+    /// Synthetic commits name their source instead, as their patch id is no commit
+    pub fn short_id(&self) -> String {
+        match self.uncommitted {
+            Some(source) => source.label().to_string(),
+            None => self.hash[..7.min(self.hash.len())].to_string(),
+        }
+    }
+
+    /// Returns indices sorted in FileTree display order (directory -> filename)
+    pub fn sorted_file_indices(&self) -> Vec<usize> {
+        let mut indices: Vec<usize> = (0..self.changes.len()).collect();
+        indices.sort_by_key(|&index| {
+            let path = &self.changes[index].path;
+            let parts: Vec<&str> = path.split('/').collect();
+
+            if parts.len() == 1 {
+                // Root level file: ("", filename)
+                (String::new(), path.clone())
+            } else {
+                // File in directory: (directory, filename)
+                let dir = parts[..parts.len() - 1].join("/");
+                let filename = parts[parts.len() - 1].to_string();
+                (dir, filename)
+            }
+        });
+        indices
+    }
+}
+
+impl GitRepository {
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let repo = Repository::open(path).context("Failed to open Git repository")?;
+        Ok(Self {
+            repo,
+            commit_cache: None,
+            commit_index: 0,
+            commit_range: None,
+            filter: CommitFilter::new(),
+            refs: RefSelection::default(),
+            tips: Vec::new(),
+            commit_labels: HashMap::new(),
+            followed_tips: None,
+            rng: ChaCha8Rng::from_os_rng(),
+        })
+    }
+
+    /// Make random commit selection repeat the same sequence for the same seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = ChaCha8Rng::seed_from_u64(seed);
+    }
+
+    /// URLs of the repository's remotes
+    pub fn remote_urls(&self) -> Vec<String> {
+        let Ok(remotes) = self.repo.remotes() else {
+            return Vec::new();
+        };
+        remotes
+            .iter()
+            .flatten()
+            .filter_map(|name| Some(self.repo.find_remote(name).ok()?.url()?.to_string()))
+            .collect()
+    }
+
+    /// Play the history of the selected refs instead of HEAD
+    pub fn set_refs(&mut self, refs: RefSelection) -> Result<()> {
+        self.tips = refs.resolve(&self.repo)?;
+        self.refs = refs;
+        self.commit_cache = None;
+        Ok(())
+    }
+
+    /// Restrict which commits are played (call before setting a commit range)
+    pub fn set_filter(&mut self, filter: CommitFilter) {
+        self.filter = filter;
+        self.commit_cache = None;
+    }
+
+    pub fn get_commit(&self, hash: &str) -> Result<CommitMetadata> {
+        let obj = self
+            .repo
+            .revparse_single(hash)
+            .context("Invalid commit hash or commit not found")?;
+
+        let commit = obj.peel_to_commit().context("Object is not a commit")?;
+
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn random_commit(&mut self) -> Result<CommitMetadata> {
+        self.populate_cache()?;
+
+        let candidates = self.commit_cache.as_ref().unwrap();
+        let selected_oid = candidates
+            .get(self.rng.random_range(0..candidates.len()))
+            .context("Failed to select random commit")?;
+
+        let commit = self.repo.find_commit(*selected_oid)?;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn next_asc_commit(&mut self) -> Result<CommitMetadata> {
+        self.populate_cache()?;
+
+        let candidates = self.commit_cache.as_ref().unwrap();
+
+        if candidates.is_empty() {
+            anyhow::bail!("No non-merge commits found in repository");
+        }
+
+        if self.commit_index >= candidates.len() {
+            anyhow::bail!("All commits have been played");
+        }
+
+        // Asc order: oldest first (reverse of cache order)
+        let asc_index = candidates.len() - 1 - self.commit_index;
+        let selected_oid = candidates
+            .get(asc_index)
+            .context("Failed to select commit")?;
+
+        let commit = self.repo.find_commit(*selected_oid)?;
+        self.commit_index += 1;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn next_desc_commit(&mut self) -> Result<CommitMetadata> {
+        self.populate_cache()?;
+
+        let candidates = self.commit_cache.as_ref().unwrap();
+
+        if candidates.is_empty() {
+            anyhow::bail!("No non-merge commits found in repository");
+        }
+
+        if self.commit_index >= candidates.len() {
+            anyhow::bail!("All commits have been played");
+        }
+
+        // Desc order: newest first (same as cache order)
+        let selected_oid = candidates
+            .get(self.commit_index)
+            .context("Failed to select commit")?;
+
+        let commit = self.repo.find_commit(*selected_oid)?;
+        self.commit_index += 1;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn reset_index(&mut self) {
+        self.commit_index = 0;
+    }
+
+    pub fn set_commit_range(&mut self, range: &str) -> Result<()> {
+        let commits = self.parse_commit_range(range)?;
+        self.commit_range = Some(commits);
+        self.commit_index = 0;
+        Ok(())
+    }
+
+    pub fn next_range_commit_asc(&mut self) -> Result<CommitMetadata> {
+        let commits = self.commit_range.as_ref().context("Commit range not set")?;
+
+        if commits.is_empty() {
+            anyhow::bail!("No commits in range");
+        }
+
+        if self.commit_index >= commits.len() {
+            anyhow::bail!("All commits in range have been played");
+        }
+
+        let selected_oid = commits
+            .get(self.commit_index)
+            .context("Failed to select commit")?;
+
+        let commit = self.repo.find_commit(*selected_oid)?;
+        self.commit_index += 1;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn next_range_commit_desc(&mut self) -> Result<CommitMetadata> {
+        let commits = self.commit_range.as_ref().context("Commit range not set")?;
+
+        if commits.is_empty() {
+            anyhow::bail!("No commits in range");
+        }
+
+        if self.commit_index >= commits.len() {
+            anyhow::bail!("All commits in range have been played");
+        }
+
+        // Desc order: newest first (reverse of asc)
+        let desc_index = commits.len() - 1 - self.commit_index;
+        let selected_oid = commits.get(desc_index).context("Failed to select commit")?;
+
+        let commit = self.repo.find_commit(*selected_oid)?;
+        self.commit_index += 1;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    pub fn random_range_commit(&mut self) -> Result<CommitMetadata> {
+        let commits = self.commit_range.as_ref().context("Commit range not set")?;
+
+        if commits.is_empty() {
+            anyhow::bail!("No commits in range");
+        }
+
+        let selected_oid = commits
+            .get(self.rng.random_range(0..commits.len()))
+            .context("Failed to select random commit")?;
+
+        let commit = self.repo.find_commit(*selected_oid)?;
+        self.extract_metadata_with_changes(&commit)
+    }
+
+    fn parse_commit_range(&self, range: &str) -> Result<Vec<Oid>> {
+        // Reject symmetric difference operator (not supported)
+        if range.contains("...") {
+            anyhow::bail!(
+                "Symmetric difference operator '...' is not supported. Use '..' instead (e.g., 'HEAD~5..HEAD')"
+            );
+        }
+
+        if !range.contains("..") {
+            anyhow::bail!(
+                "Invalid range format: {}. Use formats like 'HEAD~5..HEAD' or 'abc123..'",
+                range
+            );
+        }
+
+        let parts: Vec<&str> = range.split("..").collect();
+        if parts.len() != 2 {
+            anyhow::bail!("Invalid range format: {}", range);
+        }
+
+        let start = if parts[0].is_empty() {
+            None
+        } else {
+            Some(self.repo.revparse_single(parts[0])?.id())
+        };
+
+        let end = if parts[1].is_empty() {
+            self.repo.head()?.peel_to_commit()?.id()
+        } else {
+            self.repo.revparse_single(parts[1])?.id()
+        };
+
+        let mut revwalk = self.repo.revwalk()?;
+        revwalk.push(end)?;
+        if self.filter.first_parent() {
+            revwalk.simplify_first_parent()?;
+        }
+
+        if let Some(start_oid) = start {
+            revwalk.hide(start_oid)?;
+        }
+
+        let mut commits = self.collect_candidates(revwalk);
+        commits.reverse();
+        Ok(commits)
+    }
+
+    fn populate_cache(&mut self) -> Result<()> {
+        if self.commit_cache.is_none() {
+            self.tips = self.refs.resolve(&self.repo)?;
+
+            let mut revwalk = self.repo.revwalk()?;
+            for tip in &self.tips {
+                revwalk.push(tip.oid)?;
+            }
+            if self.tips.len() > 1 {
+                // Interleave the histories of all refs by date
+                revwalk.set_sorting(Sort::TIME)?;
+            }
+            if self.filter.first_parent() {
+                revwalk.simplify_first_parent()?;
+            }
+
+            let candidates = self.collect_candidates(revwalk);
+            self.commit_labels = self.label_commits()?;
+
+            if candidates.is_empty() {
+                if self.filter.is_empty() && !self.filter.include_merges() {
+                    anyhow::bail!("No non-merge commits found in repository");
+                }
+                anyhow::bail!("No commits match the given filters");
+            }
+
+            self.commit_cache = Some(candidates);
+        }
+        Ok(())
+    }
+
+    /// Commits that landed on the selected refs since the last call, oldest first
+    ///
+    /// The first call only records the current tips. New commits go through
+    /// the filter like the rest of history and are labeled with the ref they
+    /// appeared on.
+    pub fn poll_new_commits(&mut self) -> Result<Vec<Oid>> {
+        let tips = self.refs.resolve(&self.repo)?;
+        let Some(known) = self.followed_tips.replace(tips.clone()) else {
+            return Ok(Vec::new());
+        };
+
+        let mut seen = HashSet::new();
+        let mut arrived = Vec::new();
+        for tip in tips.iter().filter(|tip| !known.contains(tip)) {
+            let mut revwalk = self.repo.revwalk()?;
+            revwalk.push(tip.oid)?;
+            for old in &known {
+                // A tip rewritten by a force push may no longer exist
+                let _ = revwalk.hide(old.oid);
+            }
+            if self.filter.first_parent() {
+                revwalk.simplify_first_parent()?;
+            }
+            for oid in self.collect_candidates(revwalk) {
+                if seen.insert(oid) {
+                    self.commit_labels.insert(oid, tip.label.clone());
+                    arrived.push(oid);
+                }
+            }
+        }
+
+        // Revwalks yield newest first; play in the order the commits were made
+        arrived.reverse();
+        arrived.sort_by_key(|oid| {
+            self.repo
+                .find_commit(*oid)
+                .map(|commit| commit.committer().when().seconds())
+                .unwrap_or(0)
+        });
+
+        self.tips = tips;
+        Ok(arrived)
+    }
+
+    /// Attribute each commit to the first selected ref it is reachable from
+    fn label_commits(&self) -> Result<HashMap<Oid, String>> {
+        let mut labels = HashMap::new();
+        if self.tips.len() <= 1 {
+            return Ok(labels);
+        }
+
+        for (index, tip) in self.tips.iter().enumerate() {
+            let mut revwalk = self.repo.revwalk()?;
+            revwalk.push(tip.oid)?;
+            for earlier in &self.tips[..index] {
+                revwalk.hide(earlier.oid)?;
+            }
+            if self.filter.first_parent() {
+                revwalk.simplify_first_parent()?;
+            }
+            for oid in revwalk.filter_map(|oid| oid.ok()) {
+                labels.entry(oid).or_insert_with(|| tip.label.clone());
+            }
+        }
+        Ok(labels)
+    }
+
+    /// Branch label shown in the terminal output for a commit
+    fn label_for(&self, oid: Oid) -> String {
+        self.commit_labels
+            .get(&oid)
+            .or_else(|| self.tips.first().map(|tip| &tip.label))
+            .cloned()
+            .unwrap_or_else(|| refs::head_label(&self.repo))
+    }
+
+    /// Commits from `revwalk` accepted by the filter, newest first
+    /// Merge commits are skipped unless the filter includes them
+    fn collect_candidates(&self, revwalk: Revwalk) -> Vec<Oid> {
+        let mailmap = if self.filter.uses_identities() {
+            self.repo.mailmap().ok()
+        } else {
+            None
+        };
+
+        let mut candidates = Vec::new();
+        for oid in revwalk.filter_map(|oid| oid.ok()) {
+            if let Ok(commit) = self.repo.find_commit(oid) {
+                if (commit.parent_count() <= 1 || self.filter.include_merges())
+                    && self.filter.matches(&self.repo, &commit, mailmap.as_ref())
+                {
+                    candidates.push(oid);
+                }
+            }
+        }
+        candidates
+    }
+
+    fn extract_metadata_with_changes(&self, commit: &Git2Commit) -> Result<CommitMetadata> {
+        let hash = commit.id().to_string();
+        // Show the canonical identity when the repository has a .mailmap
+        let author = self
+            .repo
+            .mailmap()
+            .and_then(|mailmap| commit.author_with_mailmap(&mailmap))
+            .unwrap_or_else(|_| commit.author());
+        let author_name = author.name().unwrap_or("Unknown").to_string();
+        let timestamp = author.when().seconds();
+        let date = DateTime::from_timestamp(timestamp, 0).unwrap_or_else(Utc::now);
+        let message = commit.message().unwrap_or("").trim().to_string();
+
+        let changes = self.extract_changes(commit)?;
+        let merge = (commit.parent_count() > 1)
+            .then(|| MergeInfo::from_message(&message, commit.parent_count()));
+
+        Ok(CommitMetadata {
+            hash,
+            parent_hash: commit.parent_id(0).ok().map(|oid| oid.to_string()),
+            author: author_name,
+            date,
+            message,
+            changes,
+            merge,
+            ref_name: Some(self.label_for(commit.id())),
+            uncommitted: None,
+        })
+    }
+
+    /// Build a synthetic commit from the changes not yet committed on top of HEAD
+    ///
+    /// Its hash is the patch id of the diff, so it changes whenever the changes do.
+    /// It only tells changes apart and is never shown; see `CommitMetadata::short_id`.
+    pub fn uncommitted_changes(&self, source: UncommittedSource) -> Result<CommitMetadata> {
+        // An unborn HEAD diffs against the empty tree
+        let head_tree = self
+            .repo
+            .head()
+            .ok()
+            .and_then(|head| head.peel_to_tree().ok());
+        let index = self.repo.index().context("Failed to read index")?;
+
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.context_lines(3);
+
+        let diff = match source {
+            UncommittedSource::Worktree => {
+                diff_opts
+                    .include_untracked(true)
+                    .recurse_untracked_dirs(true)
+                    .show_untracked_content(true);
+                self.repo
+                    .diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut diff_opts))
+            }
+            UncommittedSource::Staged => {
+                self.repo
+                    .diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut diff_opts))
+            }
+        }
+        .context("Failed to diff uncommitted changes")?;
+
+        let changes = match source {
+            UncommittedSource::Worktree => {
+                let workdir = self
+                    .repo
+                    .workdir()
+                    .context("Repository has no working tree")?;
+                self.changes_from_diff(&diff, head_tree.as_ref(), &NewSide::Workdir(workdir))
+            }
+            UncommittedSource::Staged => {
+                self.changes_from_diff(&diff, head_tree.as_ref(), &NewSide::Index(&index))
+            }
+        };
+
+        let hash = diff
+            .patchid(None)
+            .map(|oid| oid.to_string())
+            .unwrap_or_else(|_| Oid::zero().to_string());
+        let author = self
+            .repo
+            .signature()
+            .ok()
+            .and_then(|signature| signature.name().map(String::from))
+            .unwrap_or_else(|| "You".to_string());
+
+        Ok(CommitMetadata {
+            hash,
+            parent_hash: self
+                .repo
+                .head()
+                .ok()
+                .and_then(|head| head.target())
+                .map(|oid| oid.to_string()),
+            author,
+            date: Utc::now(),
+            message: format!("Uncommitted changes ({})", source.label()),
+            changes,
+            merge: None,
+            ref_name: Some(refs::head_label(&self.repo)),
+            uncommitted: Some(source),
+        })
+    }
+
+    fn extract_changes(&self, commit: &Git2Commit) -> Result<Vec<FileChange>> {
+        let repo = &self.repo;
+        let commit_tree = commit.tree().context("Failed to get commit tree")?;
+        let parent_tree = if commit.parent_count() > 0 {
+            match commit.parent(0).and_then(|p| p.tree()) {
+                Ok(tree) => Some(tree),
+                Err(_) => return Ok(Vec::new()), // Skip if parent tree unavailable
+            }
+        } else {
+            None
+        };
+
+        let mut diff_opts = DiffOptions::new();
+        diff_opts.context_lines(3);
+
+        let diff = match repo.diff_tree_to_tree(
+            parent_tree.as_ref(),
+            Some(&commit_tree),
+            Some(&mut diff_opts),
+        ) {
+            Ok(d) => d,
+            Err(_) => return Ok(Vec::new()), // Skip if diff fails
+        };
+
+        Ok(self.changes_from_diff(&diff, parent_tree.as_ref(), &NewSide::Tree(&commit_tree)))
+    }
+
+    /// Build file changes from a diff whose old side is `old_tree`
+    fn changes_from_diff(
+        &self,
+        diff: &Diff,
+        old_tree: Option<&Tree>,
+        new_side: &NewSide,
+    ) -> Vec<FileChange> {
+        let repo = &self.repo;
+        let mut changes = Vec::new();
+
+        for i in 0..diff.deltas().len() {
+            let delta = diff.get_delta(i).unwrap();
+            let status = FileStatus::from(delta.status());
+
+            let path = delta
+                .new_file()
+                .path()
+                .or_else(|| delta.old_file().path())
+                .and_then(|p| p.to_str())
+                .unwrap_or("unknown")
+                .to_string();
+
+            let old_path = if delta.status() == Delta::Renamed {
+                delta
+                    .old_file()
+                    .path()
+                    .and_then(|p| p.to_str())
+                    .map(String::from)
+            } else {
+                None
+            };
+
+            let is_binary = delta.new_file().is_binary() || delta.old_file().is_binary();
+
+            // Files outside --path are listed without content and never animated
+            let in_scope = self.filter.path_in_scope(&path)
+                || old_path
+                    .as_deref()
+                    .is_some_and(|old| self.filter.path_in_scope(old));
+            if !in_scope {
+                changes.push(FileChange {
+                    path,
+                    old_path,
+                    status,
+                    is_binary,
+                    is_excluded: false,
+                    is_out_of_scope: true,
+                    exclusion_reason: None,
+                    old_content: None,
+                    new_content: None,
+                    hunks: Vec::new(),
+                    diff: String::new(),
+                    language: None,
+                });
+                continue;
+            }
+
+            let old_content = if let Some(old_tree) = old_tree {
+                if let Some(old_file_path) = delta.old_file().path() {
+                    old_tree
+                        .get_path(old_file_path)
+                        .ok()
+                        .and_then(|entry| repo.find_blob(entry.id()).ok())
+                        .and_then(|blob| {
+                            if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
+                                Some(String::from_utf8_lossy(blob.content()).to_string())
+                            } else {
+                                None
+                            }
+                        })
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            let new_content = delta
+                .new_file()
+                .path()
+                .and_then(|new_file_path| new_side.read(repo, new_file_path));
+
+            let mut hunks = Vec::new();
+            let mut diff_text = String::new();
+
+            if let Ok(Some(mut patch)) = git2::Patch::from_diff(diff, i) {
+                if let Ok(patch_str) = patch.to_buf() {
+                    diff_text = String::from_utf8_lossy(patch_str.as_ref()).to_string();
+                }
+
+                if !is_binary {
+                    for hunk_idx in 0..patch.num_hunks() {
+                        if let Ok((hunk, _hunk_lines)) = patch.hunk(hunk_idx) {
+                            let mut lines = Vec::new();
+                            let num_lines = patch.num_lines_in_hunk(hunk_idx).unwrap_or(0);
+
+                            let mut old_line_no = hunk.old_start() as usize;
+                            let mut new_line_no = hunk.new_start() as usize;
+
+                            for line_idx in 0..num_lines {
+                                if let Ok(line) = patch.line_in_hunk(hunk_idx, line_idx) {
+                                    let content =
+                                        String::from_utf8_lossy(line.content()).to_string();
+                                    let origin = line.origin();
+
+                                    let (change_type, old_no, new_no) = match origin {
+                                        '+' => {
+                                            let no = new_line_no;
+                                            new_line_no += 1;
+                                            (LineChangeType::Addition, None, Some(no))
+                                        }
+                                        '-' => {
+                                            let no = old_line_no;
+                                            old_line_no += 1;
+                                            (LineChangeType::Deletion, Some(no), None)
+                                        }
+                                        _ => {
+                                            let old_no = old_line_no;
+                                            let new_no = new_line_no;
+                                            old_line_no += 1;
+                                            new_line_no += 1;
+                                            (LineChangeType::Context, Some(old_no), Some(new_no))
+                                        }
+                                    };
+
+                                    lines.push(LineChange {
+                                        change_type,
+                                        content,
+                                        old_line_no: old_no,
+                                        new_line_no: new_no,
+                                    });
+                                }
+                            }
+
+                            hunks.push(DiffHunk {
+                                old_start: hunk.old_start() as usize,
+                                old_lines: hunk.old_lines() as usize,
+                                new_start: hunk.new_start() as usize,
+                                new_lines: hunk.new_lines() as usize,
+                                lines,
+                            });
+                        }
+                    }
+                }
+            }
+
+            // Calculate total changed lines (additions + deletions)
+            let total_changed_lines: usize = hunks
+                .iter()
+                .flat_map(|hunk| &hunk.lines)
+                .filter(|line| !matches!(line.change_type, LineChangeType::Context))
+                .count();
+
+            // Determine exclusion reason
+            let (is_excluded, exclusion_reason) = if should_exclude_file(&path) {
+                (true, Some("lock/generated file".to_string()))
+            } else if total_changed_lines > MAX_CHANGE_LINES {
+                (
+                    true,
+                    Some(format!("too many changes ({} lines)", total_changed_lines)),
+                )
+            } else {
+                (false, None)
+            };
+
+            let language = new_side.linguist_language(repo, &path);
+            changes.push(FileChange {
+                path,
+                old_path,
+                status,
+                is_binary,
+                is_excluded,
+                is_out_of_scope: false,
+                exclusion_reason,
+                old_content,
+                new_content,
+                hunks,
+                diff: diff_text,
+                language,
+            });
+        }
+
+        changes
+    }
+}
+
+/// Where the new side of a diff is read from
+enum NewSide<'a> {
+    Tree(&'a Tree<'a>),
+    Index(&'a Index),
+    Workdir(&'a Path),
+}
+
+impl NewSide<'_> {
+    /// Text content of `path`, or `None` for binary, oversized or missing files
+    fn read(&self, repo: &Repository, path: &Path) -> Option<String> {
+        let blob_text = |oid: Oid| {
+            repo.find_blob(oid).ok().and_then(|blob| {
+                if !blob.is_binary() && blob.size() <= MAX_BLOB_SIZE {
+                    Some(String::from_utf8_lossy(blob.content()).to_string())
+                } else {
+                    None
+                }
+            })
+        };
+
+        match self {
+            NewSide::Tree(tree) => tree
+                .get_path(path)
+                .ok()
+                .and_then(|entry| blob_text(entry.id())),
+            NewSide::Index(index) => index
+                .get_path(path, 0)
+                .and_then(|entry| blob_text(entry.id)),
+            NewSide::Workdir(workdir) => {
+                let full_path = workdir.join(path);
+                if std::fs::metadata(&full_path).ok()?.len() > MAX_BLOB_SIZE as u64 {
+                    return None;
+                }
+                let bytes = std::fs::read(full_path).ok()?;
+                // Same heuristic as git: a NUL byte near the start means binary
+                if bytes.iter().take(8000).any(|&b| b == 0) {
+                    return None;
+                }
+                Some(String::from_utf8_lossy(&bytes).to_string())
+            }
+        }
+    }
+
+    /// Language override for `path` from `.gitattributes`
+    /// (e.g. `*.inc linguist-language=PHP`), as of this side of the diff
+    fn linguist_language(&self, repo: &Repository, path: &str) -> Option<String> {
+        let flags = match self {
+            NewSide::Tree(tree) => return attributes::tree_linguist_language(repo, tree, path),
+            NewSide::Index(_) => AttrCheckFlags::INDEX_ONLY,
+            NewSide::Workdir(_) => AttrCheckFlags::FILE_THEN_INDEX,
+        };
+        let value = repo
+            .get_attr(Path::new(path), "linguist-language", flags)
+            .ok()?;
+        match AttrValue::from_string(value) {
+            AttrValue::String(language) => Some(language.to_string()),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixture::Fixture;
+    use super::*;
+
+    #[test]
+    fn test_should_exclude_lock_files() {
+        // JavaScript/Node.js
+        assert!(should_exclude_file("package-lock.json"));
+        assert!(should_exclude_file("yarn.lock"));
+        assert!(should_exclude_file("pnpm-lock.yaml"));
+        // Rust
+        assert!(should_exclude_file("Cargo.lock"));
+        // Ruby
+        assert!(should_exclude_file("Gemfile.lock"));
+        // Python
+        assert!(should_exclude_file("poetry.lock"));
+        assert!(should_exclude_file("Pipfile.lock"));
+        // PHP
+        assert!(should_exclude_file("composer.lock"));
+        // Go
+        assert!(should_exclude_file("go.sum"));
+        // Swift
+        assert!(should_exclude_file("Package.resolved"));
+        // Dart/Flutter
+        assert!(should_exclude_file("pubspec.lock"));
+        // .NET/C#
+        assert!(should_exclude_file("packages.lock.json"));
+        assert!(should_exclude_file("project.assets.json"));
+        // Elixir
+        assert!(should_exclude_file("mix.lock"));
+        // Java/Gradle
+        assert!(should_exclude_file("gradle.lockfile"));
+        assert!(should_exclude_file("buildscript-gradle.lockfile"));
+        // Scala
+        assert!(should_exclude_file("build.sbt.lock"));
+        // Bazel
+        assert!(should_exclude_file("MODULE.bazel.lock"));
+    }
+
+    #[test]
+    fn test_should_exclude_lock_files_with_path() {
+        assert!(should_exclude_file("path/to/package-lock.json"));
+        assert!(should_exclude_file("src/Cargo.lock"));
+        assert!(should_exclude_file("frontend/yarn.lock"));
+    }
+
+    #[test]
+    fn test_should_exclude_minified_files() {
+        assert!(should_exclude_file("bundle.min.js"));
+        assert!(should_exclude_file("app.min.css"));
+        assert!(should_exclude_file("vendor.bundle.js"));
+        assert!(should_exclude_file("styles.bundle.css"));
+        // Source maps
+        assert!(should_exclude_file("app.js.map"));
+        assert!(should_exclude_file("styles.css.map"));
+        assert!(should_exclude_file("types.d.ts.map"));
+    }
+
+    #[test]
+    fn test_should_exclude_minified_files_with_path() {
+        assert!(should_exclude_file("dist/bundle.min.js"));
+        assert!(should_exclude_file("public/assets/app.min.css"));
+    }
+
+    #[test]
+    fn test_should_not_exclude_normal_files() {
+        assert!(!should_exclude_file("src/main.rs"));
+        assert!(!should_exclude_file("package.json"));
+        assert!(!should_exclude_file("Cargo.toml"));
+        assert!(!should_exclude_file("app.js"));
+        assert!(!should_exclude_file("styles.css"));
+        assert!(!should_exclude_file("lock.txt"));
+        assert!(!should_exclude_file("minify.rs"));
+    }
+
+    #[test]
+    fn test_should_exclude_snapshot_files() {
+        assert!(should_exclude_file("component.test.ts.snap"));
+        assert!(should_exclude_file("tests/__snapshots__/test.snap"));
+        assert!(should_exclude_file("__snapshots__/component.snap"));
+        assert!(should_exclude_file("src/__snapshots__/app.test.js.snap"));
+    }
+
+    #[test]
+    fn test_user_patterns_integration() {
+        // Test all pattern types in one test since OnceLock can only be set once
+        let patterns = vec![
+            "*.svg".to_string(),
+            "*.ipynb".to_string(),
+            "dist/**".to_string(),
+            "node_modules/**".to_string(),
+        ];
+
+        // Only initialize if not already initialized
+        let _ = init_ignore_patterns(&patterns);
+
+        // Test file extension patterns
+        assert!(should_exclude_file("diagram.svg"));
+        assert!(should_exclude_file("path/to/notebook.ipynb"));
+        assert!(should_exclude_file("assets/icon.svg"));
+        assert!(!should_exclude_file("image.png"));
+        assert!(!should_exclude_file("script.py"));
+
+        // Test directory patterns
+        assert!(should_exclude_file("dist/bundle.js"));
+        assert!(should_exclude_file("dist/css/main.css"));
+        assert!(should_exclude_file("node_modules/pkg/index.js"));
+        assert!(!should_exclude_file("src/index.js"));
+    }
+
+    #[test]
+    fn test_merge_info_from_message() {
+        let github = MergeInfo::from_message(
+            "Merge pull request #42 from alice/feature/payments\n\nAdd refunds",
+            2,
+        );
+        assert_eq!(github.branch.as_deref(), Some("alice/feature/payments"));
+        assert_eq!(github.pull_request, Some(42));
+
+        let local = MergeInfo::from_message("Merge branch 'feature/x' into 'main'", 2);
+        assert_eq!(local.branch.as_deref(), Some("feature/x"));
+        assert_eq!(local.pull_request, None);
+
+        let remote = MergeInfo::from_message("Merge remote-tracking branch 'origin/dev'", 2);
+        assert_eq!(remote.branch.as_deref(), Some("origin/dev"));
+
+        let bitbucket = MergeInfo::from_message("Merged in feature/y (pull request #7)", 2);
+        assert_eq!(bitbucket.branch.as_deref(), Some("feature/y"));
+        assert_eq!(bitbucket.pull_request, Some(7));
+
+        let octopus = MergeInfo::from_message("Merge branches 'a', 'b' and 'c'", 4);
+        assert_eq!(octopus.branch, None);
+        assert_eq!(octopus.parent_count, 4);
+    }
+
+    #[test]
+    fn test_empty_patterns() {
+        let patterns: Vec<String> = vec![];
+        assert!(init_ignore_patterns(&patterns).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_pattern() {
+        let patterns = vec!["[invalid".to_string()];
+        assert!(init_ignore_patterns(&patterns).is_err());
+    }
+
+    #[test]
+    fn test_linguist_language_attribute() {
+        let fixture = Fixture::new("attrs");
+        let first = fixture.commit(
+            "Add overrides",
+            &[
+                (".gitattributes", "*.inc linguist-language=PHP\n"),
+                ("lib/db.inc", "<?php echo 1;\n"),
+            ],
+        );
+        let second = fixture.commit(
+            "Drop overrides",
+            &[(".gitattributes", ""), ("lib/db.inc", "<?php echo 2;\n")],
+        );
+        // Only the first commit has the override, the worktree has a different one
+        std::fs::write(
+            fixture.dir.join(".gitattributes"),
+            "*.inc linguist-language=C\n",
+        )
+        .unwrap();
+        std::fs::write(fixture.dir.join("lib/db.inc"), "int x;\n").unwrap();
+
+        let language = |hash: &str| {
+            fixture
+                .metadata(hash)
+                .changes
+                .iter()
+                .find(|change| change.path == "lib/db.inc")
+                .and_then(|change| change.language.clone())
+        };
+        assert_eq!(language(&first).as_deref(), Some("PHP"));
+        assert_eq!(language(&second), None);
+
+        let worktree = fixture
+            .open()
+            .uncommitted_changes(UncommittedSource::Worktree)
+            .unwrap();
+        let db = worktree
+            .changes
+            .iter()
+            .find(|change| change.path == "lib/db.inc")
+            .unwrap();
+        assert_eq!(db.language.as_deref(), Some("C"));
+    }
+
+    #[test]
+    fn test_seeded_random_commits_repeat() {
+        let fixture = Fixture::new("seed");
+        for i in 0..8 {
+            fixture.commit(
+                &format!("Commit {}", i),
+                &[("file.txt", &format!("{}\n", i))],
+            );
+        }
+
+        let picks = |seed| {
+            let mut repo = fixture.open();
+            repo.set_seed(seed);
+            (0..6)
+                .map(|_| repo.random_commit().unwrap().hash)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(picks(42), picks(42));
+    }
+}
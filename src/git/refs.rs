@@ -0,0 +1,156 @@
+use anyhow::{Context, Result};
+use git2::{BranchType, Oid, Repository};
+
+/// Refs whose history is played, as selected on the command line
+#[derive(Debug, Clone, Default)]
+pub struct RefSelection {
+    pub branches: Vec<String>,
+    /// All local branches
+    pub all: bool,
+    pub tags: bool,
+    /// Remote names whose remote-tracking branches are played
+    pub remotes: Vec<String>,
+}
+
+/// A resolved ref and the branch label shown while its commits play
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefTip {
+    pub label: String,
+    pub oid: Oid,
+}
+
+impl RefSelection {
+    pub fn is_empty(&self) -> bool {
+        self.branches.is_empty() && !self.all && !self.tags && self.remotes.is_empty()
+    }
+
+    /// Resolve the selection to commit tips, defaulting to HEAD
+    pub fn resolve(&self, repo: &Repository) -> Result<Vec<RefTip>> {
+        if self.is_empty() {
+            let oid = repo
+                .head()
+                .and_then(|head| head.peel_to_commit())
+                .context("Failed to resolve HEAD")?
+                .id();
+            return Ok(vec![RefTip {
+                label: head_label(repo),
+                oid,
+            }]);
+        }
+
+        let mut tips = Vec::new();
+
+        for name in &self.branches {
+            tips.push(resolve_branch(repo, name)?);
+        }
+
+        if self.all {
+            let mut local = Vec::new();
+            for branch in repo.branches(Some(BranchType::Local))? {
+                let (branch, _) = branch?;
+                if let (Ok(Some(name)), Ok(commit)) = (branch.name(), branch.get().peel_to_commit())
+                {
+                    local.push(RefTip {
+                        label: name.to_string(),
+                        oid: commit.id(),
+                    });
+                }
+            }
+            // History shared with the checked out branch is attributed to it
+            let current = head_label(repo);
+            local.sort_by_key(|tip| tip.label != current);
+            tips.extend(local);
+        }
+
+        if self.tags {
+            for name in repo.tag_names(None)?.iter().flatten() {
+                // Tags pointing at trees or blobs are skipped
+                if let Ok(commit) = repo
+                    .revparse_single(&format!("refs/tags/{}", name))
+                    .and_then(|object| object.peel_to_commit())
+                {
+                    tips.push(RefTip {
+                        label: name.to_string(),
+                        oid: commit.id(),
+                    });
+                }
+            }
+        }
+
+        for remote in &self.remotes {
+            repo.find_remote(remote)
+                .with_context(|| format!("Remote not found: {}", remote))?;
+            let prefix = format!("{}/", remote);
+            for branch in repo.branches(Some(BranchType::Remote))? {
+                let (branch, _) = branch?;
+                let Ok(Some(name)) = branch.name() else {
+                    continue;
+                };
+                let Some(short) = name.strip_prefix(&prefix) else {
+                    continue;
+                };
+                if short == "HEAD" {
+                    continue;
+                }
+                if let Ok(commit) = branch.get().peel_to_commit() {
+                    tips.push(RefTip {
+                        label: short.to_string(),
+                        oid: commit.id(),
+                    });
+                }
+            }
+        }
+
+        // The same ref can be selected more than once (e.g. --branch main --all)
+        let mut unique: Vec<RefTip> = Vec::new();
+        for tip in tips {
+            if !unique.contains(&tip) {
+                unique.push(tip);
+            }
+        }
+
+        if unique.is_empty() {
+            anyhow::bail!("No branches or tags matched the given ref options");
+        }
+        Ok(unique)
+    }
+}
+
+/// Name of the checked out branch, or `HEAD` when detached
+pub fn head_label(repo: &Repository) -> String {
+    repo.head()
+        .ok()
+        .filter(|head| head.is_branch())
+        .and_then(|head| head.shorthand().map(String::from))
+        .unwrap_or_else(|| "HEAD".to_string())
+}
+
+/// Look up a local branch, then a remote-tracking branch, then any revision
+fn resolve_branch(repo: &Repository, name: &str) -> Result<RefTip> {
+    if let Ok(branch) = repo.find_branch(name, BranchType::Local) {
+        let commit = branch.get().peel_to_commit()?;
+        return Ok(RefTip {
+            label: name.to_string(),
+            oid: commit.id(),
+        });
+    }
+
+    if let Ok(branch) = repo.find_branch(name, BranchType::Remote) {
+        let commit = branch.get().peel_to_commit()?;
+        // Shown as the branch it tracks: origin/feature -> feature
+        let label = name.split_once('/').map_or(name, |(_, short)| short);
+        return Ok(RefTip {
+            label: label.to_string(),
+            oid: commit.id(),
+        });
+    }
+
+    let commit = repo
+        .revparse_single(name)
+        .and_then(|object| object.peel_to_commit())
+        .with_context(|| format!("Branch not found: {}", name))?;
+    Ok(RefTip {
+        label: name.to_string(),
+        oid: commit.id(),
+    })
+}
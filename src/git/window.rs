@@ -0,0 +1,240 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use std::fmt;
+
+/// Which commit timestamp a date window is compared against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DateField {
+    #[default]
+    Author,
+    Committer,
+}
+
+/// Time bounds for commits to play, inclusive on both ends
+#[derive(Debug, Clone, Default)]
+pub struct DateWindow {
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub field: DateField,
+}
+
+impl DateWindow {
+    /// Build a window from `--since`/`--until`/`--last` style inputs
+    pub fn parse(
+        since: Option<&str>,
+        until: Option<&str>,
+        last: Option<&str>,
+        field: DateField,
+    ) -> Result<Self> {
+        let now = Utc::now();
+        let since = match (since, last) {
+            (_, Some(last)) => Some(before(now, parse_duration(last)?, last)?),
+            (Some(since), None) => Some(parse_date(since, now)?),
+            (None, None) => None,
+        };
+        let until = until.map(|until| parse_end_date(until, now)).transpose()?;
+
+        if let (Some(since), Some(until)) = (since, until) {
+            if since > until {
+                if last.is_some() {
+                    anyhow::bail!("--last window starts after --until");
+                }
+                anyhow::bail!("--since must be earlier than --until");
+            }
+        }
+
+        Ok(Self {
+            since,
+            until,
+            field,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none() && self.until.is_none()
+    }
+
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.since.is_none_or(|since| time >= since) && self.until.is_none_or(|until| time <= until)
+    }
+}
+
+impl fmt::Display for DateWindow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = |time: &DateTime<Utc>| {
+            time.with_timezone(&Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        };
+        let since = self.since.as_ref().map(format);
+        let until = self.until.as_ref().map(format);
+        write!(
+            f,
+            "{} → {}",
+            since.as_deref().unwrap_or("beginning"),
+            until.as_deref().unwrap_or("now")
+        )?;
+        if self.field == DateField::Committer {
+            write!(f, " (committer date)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parse an absolute date (`2025-01-31`, `2025-01-31 14:00`, RFC 3339)
+/// or a relative one (`30d`, meaning 30 days ago)
+pub fn parse_date(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let input = input.trim();
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Ok(time.with_timezone(&Utc));
+    }
+
+    for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(input, format) {
+            return local_to_utc(naive, input);
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return local_to_utc(date.and_hms_opt(0, 0, 0).unwrap(), input);
+    }
+
+    if let Ok(duration) = parse_duration(input) {
+        return before(now, duration, input);
+    }
+
+    anyhow::bail!(
+        "Invalid date: {}. Use YYYY-MM-DD, YYYY-MM-DD HH:MM, RFC 3339, or a relative age like 30d",
+        input
+    )
+}
+
+/// Like `parse_date`, but a bare `YYYY-MM-DD` covers the whole day
+fn parse_end_date(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    match NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d") {
+        Ok(date) => local_to_utc(date.and_hms_opt(23, 59, 59).unwrap(), input),
+        Err(_) => parse_date(input, now),
+    }
+}
+
+/// Parse a relative age such as `12h`, `30d`, `2w`, `6m` (months) or `1y`
+pub fn parse_duration(input: &str) -> Result<Duration> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !c.is_ascii_digit())
+        .with_context(|| format!("Missing unit in duration: {}", input))?;
+    let (amount, unit) = input.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .with_context(|| format!("Invalid duration: {}", input))?;
+
+    let duration = match unit {
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        "m" => amount.checked_mul(30).and_then(Duration::try_days),
+        "y" => amount.checked_mul(365).and_then(Duration::try_days),
+        _ => anyhow::bail!(
+            "Invalid duration unit in {}. Use h, d, w, m (months) or y",
+            input
+        ),
+    };
+    duration.with_context(|| format!("Duration out of range: {}", input))
+}
+
+/// The time `duration` before `now`, for the relative age given as `input`
+fn before(now: DateTime<Utc>, duration: Duration, input: &str) -> Result<DateTime<Utc>> {
+    match now.checked_sub_signed(duration) {
+        Some(time) => Ok(time),
+        None => anyhow::bail!("Duration out of range: {}", input),
+    }
+}
+
+fn local_to_utc(naive: NaiveDateTime, input: &str) -> Result<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|time| time.with_timezone(&Utc))
+        .with_context(|| format!("Date does not exist in local time zone: {}", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration("2w").unwrap(), Duration::days(14));
+        assert_eq!(parse_duration("1y").unwrap(), Duration::days(365));
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("d").is_err());
+        assert!(parse_duration("3x").is_err());
+        assert!(parse_duration("999999999999y").is_err());
+        assert!(parse_duration("9999999999999h").is_err());
+        assert!(parse_date("999999999d", Utc::now()).is_err());
+        let error =
+            DateWindow::parse(None, None, Some("999999999999y"), DateField::Author).unwrap_err();
+        assert_eq!(error.to_string(), "Duration out of range: 999999999999y");
+    }
+
+    #[test]
+    fn test_parse_date() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            parse_date("2025-01-15T08:00:00Z", now).unwrap(),
+            Utc.with_ymd_and_hms(2025, 1, 15, 8, 0, 0).unwrap()
+        );
+        assert_eq!(
+            parse_date("7d", now).unwrap(),
+            Utc.with_ymd_and_hms(2025, 2, 22, 12, 0, 0).unwrap()
+        );
+
+        let local_midnight = Local
+            .with_ymd_and_hms(2025, 1, 15, 0, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_date("2025-01-15", now).unwrap(), local_midnight);
+        assert!(parse_date("yesterday", now).is_err());
+
+        let local_end = Local
+            .with_ymd_and_hms(2025, 1, 15, 23, 59, 59)
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_end_date("2025-01-15", now).unwrap(), local_end);
+    }
+
+    #[test]
+    fn test_window_contains() {
+        let window = DateWindow {
+            since: Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()),
+            until: Some(Utc.with_ymd_and_hms(2025, 1, 31, 0, 0, 0).unwrap()),
+            field: DateField::Author,
+        };
+        assert!(window.contains(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()));
+        assert!(window.contains(Utc.with_ymd_and_hms(2025, 1, 15, 0, 0, 0).unwrap()));
+        assert!(!window.contains(Utc.with_ymd_and_hms(2024, 12, 31, 0, 0, 0).unwrap()));
+        assert!(!window.contains(Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap()));
+        assert!(DateWindow::default().contains(Utc::now()));
+    }
+
+    #[test]
+    fn test_window_rejects_inverted_bounds() {
+        let error = DateWindow::parse(
+            Some("2025-02-01"),
+            Some("2025-01-01"),
+            None,
+            DateField::Author,
+        )
+        .unwrap_err();
+        assert_eq!(error.to_string(), "--since must be earlier than --until");
+
+        // Only the flags that were given are named
+        let error =
+            DateWindow::parse(None, Some("2020-01-01"), Some("2w"), DateField::Author).unwrap_err();
+        assert_eq!(error.to_string(), "--last window starts after --until");
+    }
+}
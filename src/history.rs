@@ -0,0 +1,255 @@
+use crate::git::CommitMetadata;
+use std::collections::VecDeque;
+
+// Maximum number of played commits remembered in the history
+const MAX_HISTORY_ENTRIES: usize = 1000;
+
+// Maximum number of entries keeping their full metadata (file contents and hunks)
+// Older entries only keep their hash and are reloaded from the repository on revisit
+const MAX_CACHED_COMMITS: usize = 16;
+
+/// A commit that has been played
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    id: u64,
+    pub hash: String,
+    /// Abbreviated hash, or the source of uncommitted changes
+    pub label: String,
+    pub author: String,
+    pub summary: String,
+    metadata: Option<CommitMetadata>,
+}
+
+impl HistoryEntry {
+    /// Full metadata, if it has not been evicted from the cache
+    pub fn metadata(&self) -> Option<&CommitMetadata> {
+        self.metadata.as_ref()
+    }
+}
+
+/// Playback history with a cursor that can move back and forth over played commits
+pub struct PlaybackHistory {
+    entries: VecDeque<HistoryEntry>,
+    /// Index of the entry currently playing
+    position: Option<usize>,
+    next_id: u64,
+    /// Entry ids holding metadata, least recently used first
+    cached_ids: VecDeque<u64>,
+    max_entries: usize,
+    max_cached: usize,
+}
+
+impl PlaybackHistory {
+    pub fn new() -> Self {
+        Self::with_capacity(MAX_HISTORY_ENTRIES, MAX_CACHED_COMMITS)
+    }
+
+    pub fn with_capacity(max_entries: usize, max_cached: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            position: None,
+            next_id: 0,
+            cached_ids: VecDeque::new(),
+            max_entries: max_entries.max(1),
+            max_cached: max_cached.max(1),
+        }
+    }
+
+    pub fn entries(&self) -> &VecDeque<HistoryEntry> {
+        &self.entries
+    }
+
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    pub fn has_next(&self) -> bool {
+        matches!(self.position, Some(pos) if pos + 1 < self.entries.len())
+    }
+
+    /// Record a newly played commit and make it the current entry
+    pub fn push(&mut self, metadata: CommitMetadata) {
+        // Replaying the same commit (e.g. single commit loop) does not grow the history
+        if let Some(pos) = self.position {
+            if pos + 1 == self.entries.len() && self.entries[pos].hash == metadata.hash {
+                self.store(pos, metadata);
+                return;
+            }
+        }
+
+        let id = self.next_id;
+        self.next_id += 1;
+        self.entries.push_back(HistoryEntry {
+            id,
+            hash: metadata.hash.clone(),
+            label: metadata.short_id(),
+            author: metadata.author.clone(),
+            summary: metadata.message.lines().next().unwrap_or("").to_string(),
+            metadata: None,
+        });
+
+        if self.entries.len() > self.max_entries {
+            if let Some(evicted) = self.entries.pop_front() {
+                self.cached_ids.retain(|&cached| cached != evicted.id);
+            }
+        }
+
+        let pos = self.entries.len() - 1;
+        self.position = Some(pos);
+        self.store(pos, metadata);
+    }
+
+    /// Move to the entry at `index`
+    pub fn select(&mut self, index: usize) -> Option<&HistoryEntry> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        self.position = Some(index);
+        self.touch(index);
+        self.entries.get(index)
+    }
+
+    /// Attach (reloaded) metadata to the entry at `index`
+    pub fn store(&mut self, index: usize, metadata: CommitMetadata) {
+        let Some(entry) = self.entries.get_mut(index) else {
+            return;
+        };
+        entry.metadata = Some(metadata);
+        self.touch(index);
+        self.evict_metadata();
+    }
+
+    fn touch(&mut self, index: usize) {
+        let Some(entry) = self.entries.get(index) else {
+            return;
+        };
+        if entry.metadata.is_none() {
+            return;
+        }
+        let id = entry.id;
+        self.cached_ids.retain(|&cached| cached != id);
+        self.cached_ids.push_back(id);
+    }
+
+    fn evict_metadata(&mut self) {
+        let current_id = self
+            .position
+            .and_then(|pos| self.entries.get(pos))
+            .map(|entry| entry.id);
+
+        while self.cached_ids.len() > self.max_cached {
+            let Some(oldest) = self.cached_ids.pop_front() else {
+                break;
+            };
+            if Some(oldest) == current_id {
+                self.cached_ids.push_back(oldest);
+                continue;
+            }
+            if let Some(entry) = self.entries.iter_mut().find(|entry| entry.id == oldest) {
+                entry.metadata = None;
+            }
+        }
+    }
+}
+
+impl Default for PlaybackHistory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn commit(hash: &str) -> CommitMetadata {
+        CommitMetadata {
+            hash: hash.to_string(),
+            parent_hash: None,
+            author: "alice".to_string(),
+            date: Utc::now(),
+            message: format!("commit {}\n\nbody", hash),
+            changes: Vec::new(),
+            merge: None,
+            ref_name: None,
+            uncommitted: None,
+        }
+    }
+
+    #[test]
+    fn test_select_moves_position() {
+        let mut history = PlaybackHistory::new();
+        history.push(commit("a"));
+        history.push(commit("b"));
+        history.push(commit("c"));
+
+        assert!(!history.has_next());
+        assert_eq!(history.select(0).unwrap().hash, "a");
+        assert_eq!(history.position(), Some(0));
+        assert!(history.has_next());
+        assert!(history.select(3).is_none());
+        assert_eq!(history.position(), Some(0));
+        assert_eq!(history.entries()[1].summary, "commit b");
+    }
+
+    #[test]
+    fn test_push_appends_after_going_back() {
+        let mut history = PlaybackHistory::new();
+        history.push(commit("a"));
+        history.push(commit("b"));
+        history.select(0);
+        history.push(commit("c"));
+
+        let hashes: Vec<&str> = history.entries().iter().map(|e| e.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["a", "b", "c"]);
+        assert_eq!(history.position(), Some(2));
+    }
+
+    #[test]
+    fn test_repeated_commit_is_not_duplicated() {
+        let mut history = PlaybackHistory::new();
+        history.push(commit("a"));
+        history.push(commit("a"));
+        assert_eq!(history.entries().len(), 1);
+    }
+
+    #[test]
+    fn test_entries_are_bounded() {
+        let mut history = PlaybackHistory::with_capacity(3, 3);
+        for hash in ["a", "b", "c", "d", "e"] {
+            history.push(commit(hash));
+        }
+        let hashes: Vec<&str> = history.entries().iter().map(|e| e.hash.as_str()).collect();
+        assert_eq!(hashes, vec!["c", "d", "e"]);
+        assert_eq!(history.position(), Some(2));
+    }
+
+    #[test]
+    fn test_metadata_cache_is_bounded() {
+        let mut history = PlaybackHistory::with_capacity(10, 2);
+        for hash in ["a", "b", "c", "d"] {
+            history.push(commit(hash));
+        }
+        let cached: Vec<bool> = history
+            .entries()
+            .iter()
+            .map(|e| e.metadata().is_some())
+            .collect();
+        assert_eq!(cached, vec![false, false, true, true]);
+
+        // Revisiting an evicted entry keeps only its hash until metadata is stored again
+        let entry = history.select(0).unwrap();
+        assert!(entry.metadata().is_none());
+        history.store(0, commit("a"));
+        assert!(history.entries()[0].metadata().is_some());
+        assert_eq!(
+            history
+                .entries()
+                .iter()
+                .filter(|e| e.metadata().is_some())
+                .count(),
+            2
+        );
+    }
+}
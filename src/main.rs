@@ -1,18 +1,27 @@
 mod animation;
+mod clock;
 mod config;
+mod export;
 mod git;
+mod history;
 mod panes;
+mod prefetch;
+mod record;
 mod syntax;
 mod theme;
+mod theme_switcher;
 mod ui;
 mod widgets;
 
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
 use config::Config;
-use git::GitRepository;
+use export::ExportFormat;
+use git::{CommitFilter, DateField, DateWindow, GitRepository, RefSelection, UncommittedSource};
+use prefetch::{CommitPrefetcher, CommitSelection};
 use std::path::{Path, PathBuf};
-use theme::Theme;
+use theme::{ColorDepth, Theme, ThemeFile};
+use theme_switcher::ThemeSwitcher;
 use ui::UI;
 
 #[derive(Debug, Clone, Copy, Default, ValueEnum)]
@@ -23,6 +32,23 @@ pub enum PlaybackOrder {
     Desc,
 }
 
+/// Timestamp compared by --since/--until/--last
+#[derive(Debug, Clone, Copy, Default, ValueEnum)]
+pub enum DateFieldArg {
+    #[default]
+    Author,
+    Committer,
+}
+
+impl From<DateFieldArg> for DateField {
+    fn from(field: DateFieldArg) -> Self {
+        match field {
+            DateFieldArg::Author => DateField::Author,
+            DateFieldArg::Committer => DateField::Committer,
+        }
+    }
+}
+
 #[derive(Parser, Debug)]
 #[command(
     name = "git-logue",
@@ -32,6 +58,7 @@ pub enum PlaybackOrder {
 )]
 pub struct Args {
     #[arg(
+        global = true,
         short,
         long,
         value_name = "PATH",
@@ -48,6 +75,7 @@ pub struct Args {
     pub commit: Option<String>,
 
     #[arg(
+        global = true,
         short,
         long,
         value_name = "MS",
@@ -56,6 +84,7 @@ pub struct Args {
     pub speed: Option<u64>,
 
     #[arg(
+        global = true,
         short,
         long,
         value_name = "NAME",
@@ -64,6 +93,7 @@ pub struct Args {
     pub theme: Option<String>,
 
     #[arg(
+        global = true,
         long,
         num_args = 0..=1,
         default_missing_value = "true",
@@ -92,6 +122,21 @@ pub struct Args {
     #[arg(long, help = "Display third-party license information")]
     pub license: bool,
 
+    #[arg(
+        long = "watch-theme",
+        help = "Reload the theme when files in the themes directory change"
+    )]
+    pub watch_theme: bool,
+
+    #[arg(
+        global = true,
+        long = "color-depth",
+        value_name = "DEPTH",
+        value_enum,
+        help = "Colors the terminal can show (default: detected from NO_COLOR, COLORTERM and TERM)"
+    )]
+    pub color_depth: Option<ColorDepth>,
+
     #[arg(
         short = 'i',
         long = "ignore",
@@ -108,6 +153,157 @@ pub struct Args {
     )]
     pub ignore_file: Option<PathBuf>,
 
+    #[arg(
+        long = "author",
+        value_name = "PATTERN",
+        action = clap::ArgAction::Append,
+        help = "Only replay commits whose author matches pattern (regex or substring on \"Name <email>\", can be specified multiple times)"
+    )]
+    pub author: Vec<String>,
+
+    #[arg(
+        long = "exclude-author",
+        value_name = "PATTERN",
+        action = clap::ArgAction::Append,
+        help = "Skip commits whose author matches pattern (can be specified multiple times)"
+    )]
+    pub exclude_author: Vec<String>,
+
+    #[arg(
+        long = "committer",
+        value_name = "PATTERN",
+        action = clap::ArgAction::Append,
+        help = "Only replay commits whose committer matches pattern (can be specified multiple times)"
+    )]
+    pub committer: Vec<String>,
+
+    #[arg(
+        long,
+        group = "uncommitted",
+        conflicts_with = "commit",
+        help = "Replay uncommitted changes (staged, unstaged and untracked) as one commit"
+    )]
+    pub worktree: bool,
+
+    #[arg(
+        long,
+        group = "uncommitted",
+        conflicts_with = "commit",
+        help = "Replay staged changes as one commit"
+    )]
+    pub staged: bool,
+
+    #[arg(
+        long,
+        requires = "uncommitted",
+        help = "Replay --worktree/--staged changes again whenever they change"
+    )]
+    pub watch: bool,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["commit", "uncommitted"],
+        help = "Play new commits on the selected branches as they land, replaying history while idle"
+    )]
+    pub follow: bool,
+
+    #[arg(
+        short,
+        long,
+        value_name = "NAME",
+        action = clap::ArgAction::Append,
+        help = "Play the history of a branch instead of HEAD (can be specified multiple times)"
+    )]
+    pub branch: Vec<String>,
+
+    #[arg(long, help = "Play the history of all local branches")]
+    pub all: bool,
+
+    #[arg(long, help = "Play the history of all tags")]
+    pub tags: bool,
+
+    #[arg(
+        long,
+        value_name = "NAME",
+        action = clap::ArgAction::Append,
+        help = "Play the history of all branches of a remote (can be specified multiple times)"
+    )]
+    pub remote: Vec<String>,
+
+    #[arg(
+        long,
+        help = "Also replay merge commits, each as one combined diff against its first parent"
+    )]
+    pub merges: bool,
+
+    #[arg(
+        long = "first-parent",
+        help = "Follow only the first parent of merge commits (mainline history)"
+    )]
+    pub first_parent: bool,
+
+    #[arg(
+        long = "grep",
+        value_name = "REGEX",
+        action = clap::ArgAction::Append,
+        help = "Only replay commits whose message matches regex (can be specified multiple times)"
+    )]
+    pub grep: Vec<String>,
+
+    #[arg(
+        long = "invert-grep",
+        requires = "grep",
+        help = "Only replay commits whose message matches none of the --grep patterns"
+    )]
+    pub invert_grep: bool,
+
+    #[arg(
+        long = "pathspec",
+        value_name = "PATTERN",
+        action = clap::ArgAction::Append,
+        help = "Only replay commits touching matching paths, and only animate those files (glob or directory, can be specified multiple times)"
+    )]
+    pub pathspec: Vec<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Only replay commits made on or after date (YYYY-MM-DD, YYYY-MM-DD HH:MM, RFC 3339, or relative like 2w)"
+    )]
+    pub since: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DATE",
+        help = "Only replay commits made on or before date (same formats as --since)"
+    )]
+    pub until: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "DURATION",
+        conflicts_with = "since",
+        help = "Only replay commits from the last duration (e.g., 12h, 30d, 2w, 6m, 1y)"
+    )]
+    pub last: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "Seed for commit selection and typing variation, so playback repeats exactly"
+    )]
+    pub seed: Option<u64>,
+
+    #[arg(
+        long = "date-field",
+        value_enum,
+        value_name = "FIELD",
+        default_value = "author",
+        help = "Timestamp compared by --since/--until/--last"
+    )]
+    pub date_field: DateFieldArg,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -119,6 +315,76 @@ pub enum Commands {
         #[command(subcommand)]
         command: ThemeCommands,
     },
+    /// List supported languages and the files highlighted with them
+    Languages,
+    /// Record the replay of a commit as an asciicast v2 file
+    Record {
+        #[arg(
+            value_name = "COMMIT",
+            default_value = "HEAD",
+            help = "Commit to record"
+        )]
+        commit: String,
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "Recording to write (e.g., demo.cast)"
+        )]
+        output: PathBuf,
+        #[arg(
+            long,
+            value_name = "COLUMNS",
+            default_value_t = 120,
+            help = "Terminal width of the recording"
+        )]
+        width: u16,
+        #[arg(
+            long,
+            value_name = "ROWS",
+            default_value_t = 36,
+            help = "Terminal height of the recording"
+        )]
+        height: u16,
+    },
+    /// Export the replay of a commit as an animated SVG or an HTML page with a player
+    Export {
+        #[arg(
+            value_name = "COMMIT",
+            default_value = "HEAD",
+            help = "Commit to export"
+        )]
+        commit: String,
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            default_value = "svg",
+            help = "Output format"
+        )]
+        format: ExportFormat,
+        #[arg(
+            short,
+            long,
+            value_name = "FILE",
+            help = "File to write (defaults to <short hash>.svg or .html)"
+        )]
+        output: Option<PathBuf>,
+        #[arg(
+            long,
+            value_name = "COLUMNS",
+            default_value_t = 120,
+            help = "Terminal width of the replay"
+        )]
+        width: u16,
+        #[arg(
+            long,
+            value_name = "ROWS",
+            default_value_t = 36,
+            help = "Terminal height of the replay"
+        )]
+        height: u16,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -130,6 +396,21 @@ pub enum ThemeCommands {
         #[arg(value_name = "NAME", help = "Theme name to set as default")]
         name: String,
     },
+    /// Convert a VS Code theme, Base16 scheme or iTerm2 preset into a user theme
+    Import {
+        #[arg(
+            value_name = "FILE",
+            help = "VS Code theme (.json), Base16 scheme (.yaml) or iTerm2 preset (.itermcolors)"
+        )]
+        file: PathBuf,
+        #[arg(long, help = "Theme name (defaults to the scheme's name)")]
+        name: Option<String>,
+        #[arg(
+            long,
+            help = "Overwrite an existing theme file, or shadow the built-in theme of the same name"
+        )]
+        force: bool,
+    },
 }
 
 impl Args {
@@ -181,8 +462,8 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // Handle subcommands
-    if let Some(command) = args.command {
+    // Handle subcommands; recording and exporting run after the shared setup below
+    if let Some(command) = &args.command {
         match command {
             Commands::Theme { command } => match command {
                 ThemeCommands::List => {
@@ -190,11 +471,23 @@ fn main() -> Result<()> {
                     for theme in Theme::available_themes() {
                         println!("  - {}", theme);
                     }
+
+                    let themes_dir = Config::themes_dir()?;
+                    let user_themes = Theme::user_themes(&themes_dir);
+                    println!();
+                    if user_themes.is_empty() {
+                        println!("No user themes in {}", themes_dir.display());
+                    } else {
+                        println!("User themes ({}):", themes_dir.display());
+                        for theme in user_themes {
+                            println!("  - {}", theme);
+                        }
+                    }
                     return Ok(());
                 }
                 ThemeCommands::Set { name } => {
                     // Validate theme exists
-                    Theme::load(&name)?;
+                    Theme::load(name, Config::themes_dir().ok().as_deref())?;
 
                     // Load existing config or create new one
                     let mut config = Config::load().unwrap_or_default();
@@ -205,12 +498,51 @@ fn main() -> Result<()> {
                     println!("Theme set to '{}' in {}", name, config_path.display());
                     return Ok(());
                 }
+                ThemeCommands::Import { file, name, force } => {
+                    let imported = theme::import::from_file(file)?;
+                    // A user theme replaces the built-in one of the same name, so only do that on request
+                    if name.is_none()
+                        && !*force
+                        && Theme::available_themes().contains(&imported.name.as_str())
+                    {
+                        anyhow::bail!(
+                            "'{}' is a built-in theme; use --name to import it under another name, or --force to shadow the built-in one",
+                            imported.name
+                        );
+                    }
+                    let name = name.clone().unwrap_or(imported.name);
+                    let path = Config::themes_dir()?.join(format!("{}.toml", name));
+                    if path.exists() && !*force {
+                        anyhow::bail!(
+                            "Theme file already exists: {} (use --force to overwrite)",
+                            path.display()
+                        );
+                    }
+
+                    let source = file.file_name().unwrap_or_default().to_string_lossy();
+                    let header = vec![format!(
+                        "Imported from {} ({})",
+                        source,
+                        imported.format.label()
+                    )];
+                    std::fs::write(&path, ThemeFile::render(&imported.theme, &header))
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+
+                    println!("Imported '{}' to {}", name, path.display());
+                    println!("Use it with: gitlogue --theme {}", name);
+                    return Ok(());
+                }
             },
+            Commands::Languages => {
+                print_languages()?;
+                return Ok(());
+            }
+            Commands::Record { .. } | Commands::Export { .. } => {}
         }
     }
 
     let repo_path = args.validate()?;
-    let repo = GitRepository::open(&repo_path)?;
+    let mut repo = GitRepository::open(&repo_path)?;
 
     let is_commit_specified = args.commit.is_some();
     let is_range_mode = args
@@ -236,7 +568,8 @@ fn main() -> Result<()> {
     }
     patterns.extend(args.ignore.clone());
     git::init_ignore_patterns(&patterns).ok();
-    let theme_name = args.theme.as_deref().unwrap_or(&config.theme);
+    syntax::init_language_mappings(&config.languages)
+        .context("Invalid [languages] table in config file")?;
     let speed = args.speed.unwrap_or(config.speed);
     let background = args.background.unwrap_or(config.background);
     let mut order = args.order.unwrap_or(match config.order.as_str() {
@@ -251,55 +584,238 @@ fn main() -> Result<()> {
     }
 
     let loop_playback = args.loop_playback.unwrap_or(config.loop_playback);
-    let mut theme = Theme::load(theme_name)?;
 
-    // Apply transparent background if requested
-    if !background {
-        theme = theme.with_transparent_background();
+    // Theme: CLI > per-repository rule > day/night schedule > config theme
+    let repo_theme = config.repo_theme(&repo_path, &repo.remote_urls())?;
+    let theme_name = args
+        .theme
+        .as_deref()
+        .or(repo_theme)
+        .unwrap_or(&config.theme);
+    // Recordings are not bound to this terminal's colors
+    let recording = matches!(
+        args.command,
+        Some(Commands::Record { .. } | Commands::Export { .. })
+    );
+    let color_depth = args.color_depth.unwrap_or_else(|| {
+        if recording {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::detect()
+        }
+    });
+    let mut themes = ThemeSwitcher::new(
+        theme_name,
+        Config::themes_dir().ok(),
+        background,
+        color_depth,
+    );
+    // Recordings ignore the time of day, so the same --seed gives the same file
+    if args.theme.is_none() && repo_theme.is_none() && !recording {
+        if let Some(schedule) = config.theme_schedule()? {
+            themes = themes.schedule(schedule);
+        }
+    }
+    if args.watch_theme {
+        themes = themes.watch();
+    }
+    // Report a broken theme before reading any commits
+    themes.load()?;
+
+    // Commit filters: CLI flags replace the corresponding config lists
+    let pick = |cli: &Vec<String>, config: &Vec<String>| {
+        if cli.is_empty() {
+            config.clone()
+        } else {
+            cli.clone()
+        }
+    };
+    let filter = CommitFilter::new()
+        .with_authors(&pick(&args.author, &config.authors))?
+        .with_excluded_authors(&pick(&args.exclude_author, &config.exclude_authors))?
+        .with_committers(&pick(&args.committer, &config.committers))?
+        .with_grep(&args.grep, args.invert_grep)?
+        .with_merges(args.merges)
+        .with_first_parent(args.first_parent)
+        .with_paths(&args.pathspec)?
+        .with_window(DateWindow::parse(
+            args.since.as_deref(),
+            args.until.as_deref(),
+            args.last.as_deref(),
+            args.date_field.into(),
+        )?);
+    let window = filter.window().clone();
+    repo.set_filter(filter);
+    if let Some(seed) = args.seed {
+        repo.set_seed(seed);
     }
+    repo.set_refs(RefSelection {
+        branches: args.branch.clone(),
+        all: args.all,
+        tags: args.tags,
+        remotes: args.remote.clone(),
+    })?;
 
     // Setup commit range if specified
     if is_range_mode {
         repo.set_commit_range(args.commit.as_ref().unwrap())?;
     }
 
-    // Load initial commit
-    let metadata = if is_range_mode {
-        match order {
-            PlaybackOrder::Random => repo.random_range_commit()?,
-            PlaybackOrder::Asc => repo.next_range_commit_asc()?,
-            PlaybackOrder::Desc => repo.next_range_commit_desc()?,
-        }
-    } else if let Some(commit_hash) = &args.commit {
-        repo.get_commit(commit_hash)?
+    let uncommitted = if args.worktree {
+        Some(UncommittedSource::Worktree)
+    } else if args.staged {
+        Some(UncommittedSource::Staged)
     } else {
-        match order {
-            PlaybackOrder::Random => repo.random_commit()?,
-            PlaybackOrder::Asc => repo.next_asc_commit()?,
-            PlaybackOrder::Desc => repo.next_desc_commit()?,
-        }
-    };
-
-    // Create UI with repository reference
-    // Range mode always needs repo reference for iteration
-    let repo_ref = if is_range_mode {
-        Some(&repo)
-    } else if is_commit_specified && !loop_playback {
         None
-    } else {
-        Some(&repo)
     };
-    let mut ui = UI::new(
-        speed,
-        repo_ref,
-        theme,
+
+    if let Some(Commands::Record {
+        commit,
+        output,
+        width,
+        height,
+    }) = &args.command
+    {
+        let metadata = repo.get_commit(commit)?;
+        let options = record::RecordOptions {
+            width: *width,
+            height: *height,
+            speed_ms: speed,
+            seed: args.seed,
+        };
+        return record::record(metadata, &options, themes, output);
+    }
+
+    if let Some(Commands::Export {
+        commit,
+        format,
+        output,
+        width,
+        height,
+    }) = &args.command
+    {
+        let metadata = repo.get_commit(commit)?;
+        let output = output.clone().unwrap_or_else(|| {
+            let extension = match format {
+                ExportFormat::Svg => "svg",
+                ExportFormat::Html => "html",
+            };
+            PathBuf::from(format!(
+                "{}.{}",
+                &metadata.hash[..7.min(metadata.hash.len())],
+                extension
+            ))
+        });
+        let options = record::RecordOptions {
+            width: *width,
+            height: *height,
+            speed_ms: speed,
+            seed: args.seed,
+        };
+        return export::export(metadata, &options, *format, themes, &output);
+    }
+
+    // Single commit mode without loop plays once and needs no background worker
+    if is_commit_specified && !is_range_mode && !loop_playback {
+        let metadata = repo.get_commit(args.commit.as_ref().unwrap())?;
+        let mut ui = UI::new(speed, None, themes)?;
+        if let Some(seed) = args.seed {
+            ui.set_seed(seed);
+        }
+        ui.load_commit(metadata);
+        ui.run()?;
+        return Ok(());
+    }
+
+    if let Some(source) = uncommitted {
+        let metadata = repo.uncommitted_changes(source)?;
+        if metadata.changes.is_empty() {
+            if !args.watch {
+                anyhow::bail!("No uncommitted changes");
+            }
+            eprintln!("Waiting for {} changes...", source.label());
+        } else if !args.watch && !loop_playback {
+            let mut ui = UI::new(speed, None, themes)?;
+            if let Some(seed) = args.seed {
+                ui.set_seed(seed);
+            }
+            ui.load_commit(metadata);
+            ui.run()?;
+            return Ok(());
+        }
+    }
+
+    // Commits are read on a worker thread so the next one is ready when the current one ends
+    let selection = CommitSelection {
         order,
-        loop_playback,
-        args.commit.clone(),
+        commit_spec: args.commit.clone(),
         is_range_mode,
-    );
-    ui.load_commit(metadata);
+        // Following never runs out of history to fall back on
+        loop_playback: loop_playback || args.follow,
+        uncommitted,
+        watch: args.watch,
+        follow: args.follow,
+    };
+    let mut source = CommitPrefetcher::spawn(repo, selection);
+    let initial = source.next_blocking(speed)?;
+
+    let mut ui = UI::new(speed, Some(source), themes)?;
+    if let Some(seed) = args.seed {
+        ui.set_seed(seed);
+    }
+    if !window.is_empty() {
+        ui.set_date_window(window.to_string());
+    }
+    ui.load_prepared(initial);
     ui.run()?;
 
     Ok(())
 }
+
+/// Print the bundled languages and the user's `[languages]` mappings
+fn print_languages() -> Result<()> {
+    let width = syntax::languages::LANGUAGES
+        .iter()
+        .map(|info| info.name.len())
+        .max()
+        .unwrap_or(0);
+
+    // Leave out fallbacks claimed by an earlier language (e.g. Dockerfile under bash)
+    let claims = |found: Option<&syntax::languages::LanguageInfo>, info| {
+        found.is_some_and(|found| std::ptr::eq(found, info))
+    };
+
+    println!("Supported languages:");
+    for info in syntax::languages::LANGUAGES {
+        let extensions = info
+            .extensions
+            .iter()
+            .filter(|extension| claims(syntax::languages::by_extension(extension), info))
+            .map(|extension| format!("*.{}", extension));
+        let filenames = info
+            .filenames
+            .iter()
+            .filter(|name| claims(syntax::languages::by_filename(name), info))
+            .map(|name| name.to_string());
+        let files: Vec<String> = extensions.chain(filenames).collect();
+        println!(
+            "  {:<width$}  {}",
+            info.name,
+            files.join(" "),
+            width = width
+        );
+    }
+
+    let config_path = Config::config_path()?;
+    let config = Config::load()?;
+    println!();
+    if config.languages.is_empty() {
+        println!("No language mappings in {}", config_path.display());
+    } else {
+        println!("Language mappings ({}):", config_path.display());
+        for (pattern, language) in &config.languages {
+            println!("  {} -> {}", pattern, language);
+        }
+    }
+    Ok(())
+}
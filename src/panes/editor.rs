@@ -62,9 +62,14 @@ impl EditorPane {
         let content = SelectableParagraph::new(visible_lines)
             .block(block)
             .selected_line(selected_line_index)
-            .selected_style(Style::default().bg(theme.editor_cursor_line_bg))
+            .selected_style(Theme::highlight(
+                theme.editor_cursor_line_bg,
+                theme.syntax_variable,
+            ))
             .background_style(Style::default().bg(theme.background_right))
             .padding(Padding::horizontal(2))
+            // Line number, its trailing space and the separator
+            .wrap_indent(line_num_width as u16 + 3)
             .dim(20, 0.6);
         f.render_widget(content, area);
     }
@@ -231,10 +236,11 @@ impl EditorPane {
                 // Cursor character - bright highlight
                 spans.push(Span::styled(
                     ch.to_string(),
-                    Style::default()
-                        .bg(ctx.theme.editor_cursor_char_bg)
-                        .fg(ctx.theme.editor_cursor_char_fg)
-                        .add_modifier(Modifier::BOLD),
+                    Theme::highlight(
+                        ctx.theme.editor_cursor_char_bg,
+                        ctx.theme.editor_cursor_char_fg,
+                    )
+                    .add_modifier(Modifier::BOLD),
                 ));
             } else {
                 // Normal character
@@ -245,10 +251,11 @@ impl EditorPane {
         if ctx.show_cursor && ctx.cursor_col >= chars.len() {
             spans.push(Span::styled(
                 " ",
-                Style::default()
-                    .bg(ctx.theme.editor_cursor_char_bg)
-                    .fg(ctx.theme.editor_cursor_char_fg)
-                    .add_modifier(Modifier::BOLD),
+                Theme::highlight(
+                    ctx.theme.editor_cursor_char_bg,
+                    ctx.theme.editor_cursor_char_fg,
+                )
+                .add_modifier(Modifier::BOLD),
             ));
         }
 
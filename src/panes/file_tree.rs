@@ -54,6 +54,11 @@ impl FileTreePane {
         self.cached_current_file_index = Some(current_file_index);
     }
 
+    /// Rebuild the cached lines on the next update, e.g. after a theme change
+    pub fn invalidate(&mut self) {
+        self.cached_metadata_id = None;
+    }
+
     pub fn render(&self, f: &mut Frame, area: Rect, theme: &Theme) {
         let block = Block::default()
             .style(Style::default().bg(theme.background_left))
@@ -67,7 +72,10 @@ impl FileTreePane {
         let content = SelectableParagraph::new(self.cached_lines.clone())
             .block(block)
             .selected_line(self.cached_current_line_index)
-            .selected_style(Style::default().bg(theme.file_tree_current_file_bg))
+            .selected_style(Theme::highlight(
+                theme.file_tree_current_file_bg,
+                theme.file_tree_current_file_fg,
+            ))
             .background_style(Style::default().bg(theme.background_left))
             .padding(Padding::horizontal(2))
             .dim(20, 0.6);
@@ -81,8 +89,20 @@ impl FileTreePane {
     ) -> (Vec<Line<'static>>, Option<usize>) {
         // Build directory tree
         let mut tree: FileTree = BTreeMap::new();
+        // Files outside --pathspec, counted per directory
+        let mut out_of_scope: BTreeMap<String, usize> = BTreeMap::new();
 
         for (index, change) in metadata.changes.iter().enumerate() {
+            if change.is_out_of_scope {
+                let dir = change
+                    .path
+                    .rsplit_once('/')
+                    .map(|(dir, _)| format!("{}/", dir))
+                    .unwrap_or_else(|| "./".to_string());
+                *out_of_scope.entry(dir).or_default() += 1;
+                continue;
+            }
+
             let (status_char, color) = match change.status.as_str() {
                 "A" => ("+", theme.file_tree_added),
                 "D" => ("-", theme.file_tree_deleted),
@@ -201,6 +221,27 @@ impl FileTreePane {
             }
         }
 
+        // Out-of-scope files are collapsed into one line per directory
+        if !out_of_scope.is_empty() {
+            let dim = Style::default()
+                .fg(theme.file_tree_default)
+                .add_modifier(Modifier::DIM);
+            let total: usize = out_of_scope.values().sum();
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(
+                format!("{} out of scope", total),
+                dim.add_modifier(Modifier::ITALIC),
+            )));
+            for (dir, count) in out_of_scope {
+                lines.push(Line::from(vec![
+                    Span::styled(format!("  ▸ {}", dir), dim),
+                    Span::styled(format!(" ({})", count), dim),
+                ]));
+            }
+        }
+
         (lines, current_line_index)
     }
 }
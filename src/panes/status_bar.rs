@@ -9,9 +9,20 @@ use ratatui::{
     Frame,
 };
 
-pub struct StatusBarPane;
+pub struct StatusBarPane {
+    /// Active date window, shown below the commit date
+    date_window: Option<String>,
+}
 
 impl StatusBarPane {
+    pub fn new() -> Self {
+        Self { date_window: None }
+    }
+
+    pub fn set_date_window(&mut self, label: Option<String>) {
+        self.date_window = label;
+    }
+
     pub fn render(
         &self,
         f: &mut Frame,
@@ -24,7 +35,10 @@ impl StatusBarPane {
             .padding(Padding::vertical(1));
 
         let status_text = if let Some(meta) = metadata {
-            let hash_short = &meta.hash[..7.min(meta.hash.len())];
+            let hash_short = match meta.uncommitted {
+                Some(_) => format!("uncommitted ({})", meta.short_id()),
+                None => meta.short_id(),
+            };
             let date_str = meta.date.format("%Y-%m-%d %H:%M:%S").to_string();
 
             let mut lines = vec![
@@ -42,6 +56,26 @@ impl StatusBarPane {
                 ]),
             ];
 
+            if let Some(merge) = &meta.merge {
+                let mut spans = vec![Span::raw("merge: ")];
+                let branch = merge.branch.as_deref().unwrap_or("(unknown branch)");
+                spans.push(Span::styled(branch, Style::default().fg(theme.status_hash)));
+                if let Some(pr) = merge.pull_request {
+                    spans.push(Span::styled(
+                        format!(" #{}", pr),
+                        Style::default().fg(theme.status_date),
+                    ));
+                }
+                lines.push(Line::from(spans));
+            }
+
+            if let Some(window) = &self.date_window {
+                lines.push(Line::from(vec![
+                    Span::raw("window: "),
+                    Span::styled(window.as_str(), Style::default().fg(theme.status_date)),
+                ]));
+            }
+
             // Add commit message lines (skip empty lines)
             for msg_line in meta.message.lines() {
                 if !msg_line.trim().is_empty() {
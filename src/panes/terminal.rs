@@ -42,10 +42,11 @@ impl TerminalPane {
                             )];
                             spans.push(Span::styled(
                                 " ",
-                                Style::default()
-                                    .bg(theme.terminal_cursor_bg)
-                                    .fg(theme.terminal_cursor_fg)
-                                    .add_modifier(Modifier::BOLD),
+                                Theme::highlight(
+                                    theme.terminal_cursor_bg,
+                                    theme.terminal_cursor_fg,
+                                )
+                                .add_modifier(Modifier::BOLD),
                             ));
                             Line::from(spans)
                         } else {
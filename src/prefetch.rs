@@ -0,0 +1,497 @@
+use anyhow::{Context, Result};
+use git2::Oid;
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::animation::{AnimationStep, StepBuilder};
+use crate::git::{CommitMetadata, GitRepository, UncommittedSource};
+use crate::PlaybackOrder;
+
+// How often uncommitted changes are checked in watch mode
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+// How often the followed refs are checked for new commits
+const FOLLOW_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Which commits to play, as selected on the command line
+#[derive(Debug, Clone)]
+pub struct CommitSelection {
+    pub order: PlaybackOrder,
+    pub commit_spec: Option<String>,
+    pub is_range_mode: bool,
+    pub loop_playback: bool,
+    /// Replay uncommitted changes instead of history
+    pub uncommitted: Option<UncommittedSource>,
+    /// Replay uncommitted changes again whenever they change
+    pub watch: bool,
+    /// Play new commits on the selected refs as soon as they land
+    pub follow: bool,
+}
+
+impl CommitSelection {
+    /// Fetch the next commit to play, starting over when looping
+    pub fn next(&self, repo: &mut GitRepository) -> Result<CommitMetadata> {
+        match self.fetch(repo) {
+            Ok(metadata) => Ok(metadata),
+            Err(_) if self.loop_playback => {
+                repo.reset_index();
+                self.fetch(repo)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn fetch(&self, repo: &mut GitRepository) -> Result<CommitMetadata> {
+        if let Some(source) = self.uncommitted {
+            let metadata = repo.uncommitted_changes(source)?;
+            if metadata.changes.is_empty() {
+                anyhow::bail!("No uncommitted changes");
+            }
+            Ok(metadata)
+        } else if self.is_range_mode {
+            match self.order {
+                PlaybackOrder::Random => repo.random_range_commit(),
+                PlaybackOrder::Asc => repo.next_range_commit_asc(),
+                PlaybackOrder::Desc => repo.next_range_commit_desc(),
+            }
+        } else if let Some(commit_spec) = &self.commit_spec {
+            repo.get_commit(commit_spec)
+        } else {
+            match self.order {
+                PlaybackOrder::Random => repo.random_commit(),
+                PlaybackOrder::Asc => repo.next_asc_commit(),
+                PlaybackOrder::Desc => repo.next_desc_commit(),
+            }
+        }
+    }
+}
+
+/// A commit with its animation steps, ready to be loaded into the engine
+pub struct PreparedCommit {
+    pub metadata: CommitMetadata,
+    pub steps: Vec<AnimationStep>,
+    /// Typing speed the steps were generated with
+    pub speed_ms: u64,
+    /// Whether the commit landed while following the refs
+    pub live: bool,
+}
+
+impl PreparedCommit {
+    fn new(metadata: CommitMetadata, speed_ms: u64) -> Self {
+        let steps = StepBuilder::build(&metadata, speed_ms);
+        Self {
+            metadata,
+            steps,
+            speed_ms,
+            live: false,
+        }
+    }
+}
+
+enum Request {
+    Next {
+        speed_ms: u64,
+    },
+    Commit {
+        hash: String,
+        speed_ms: u64,
+    },
+    /// Build the steps of a commit whose metadata is already at hand
+    Prepare {
+        metadata: Box<CommitMetadata>,
+        speed_ms: u64,
+    },
+}
+
+enum Response {
+    Next(Result<PreparedCommit>),
+    /// A commit that landed while following the refs, in answer to a next request
+    Live(Result<PreparedCommit>),
+    Commit(String, Result<PreparedCommit>),
+    /// Number of new commits queued in follow mode
+    Arrived(usize),
+}
+
+/// Reads commits and generates their animation steps on a background thread
+/// so transitions between commits don't block rendering
+pub struct CommitPrefetcher {
+    requests: Sender<Request>,
+    responses: Receiver<Response>,
+    /// Next commits received but not yet taken
+    ready: VecDeque<Result<PreparedCommit>>,
+    /// New commits received in follow mode, played before those in `ready`
+    live_ready: VecDeque<PreparedCommit>,
+    /// Number of next commits requested but not yet received
+    pending: usize,
+    /// Number of new commits queued by the worker but not yet received
+    live_queued: usize,
+    /// Commit asked for with `request_commit`, and its result once received
+    requested: Option<String>,
+    fetched: Option<Result<PreparedCommit>>,
+}
+
+impl CommitPrefetcher {
+    /// Move the repository to a worker thread serving commits for `selection`
+    pub fn spawn(mut repo: GitRepository, selection: CommitSelection) -> Self {
+        let (request_tx, request_rx) = mpsc::channel::<Request>();
+        let (response_tx, response_rx) = mpsc::channel();
+
+        thread::spawn(move || {
+            // In watch mode a next request is held until the changes differ from the last ones sent
+            let mut watching: Option<u64> = None;
+            let mut last_sent: Option<String> = None;
+            // In follow mode new commits wait here until the UI asks for the next one
+            let mut live: VecDeque<Oid> = VecDeque::new();
+            let mut last_poll = Instant::now();
+            if selection.follow {
+                // Only commits landing from now on are played live
+                let _ = repo.poll_new_commits();
+            }
+
+            // Exits once the UI drops its end of the channel
+            loop {
+                let timeout = if selection.follow {
+                    Some(FOLLOW_INTERVAL.saturating_sub(last_poll.elapsed()))
+                } else if watching.is_some() {
+                    Some(WATCH_INTERVAL)
+                } else {
+                    None
+                };
+                let request = match timeout {
+                    Some(timeout) => match request_rx.recv_timeout(timeout) {
+                        Ok(request) => Some(request),
+                        Err(RecvTimeoutError::Timeout) => None,
+                        Err(RecvTimeoutError::Disconnected) => break,
+                    },
+                    None => match request_rx.recv() {
+                        Ok(request) => Some(request),
+                        Err(_) => break,
+                    },
+                };
+
+                if selection.follow && last_poll.elapsed() >= FOLLOW_INTERVAL {
+                    last_poll = Instant::now();
+                    // Refs can be briefly unreadable while git updates them
+                    if let Ok(arrived) = repo.poll_new_commits() {
+                        if !arrived.is_empty() {
+                            let count = arrived.len();
+                            live.extend(arrived);
+                            if response_tx.send(Response::Arrived(count)).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+
+                let response = match request {
+                    Some(Request::Next { speed_ms }) if selection.watch => {
+                        watching = Some(speed_ms);
+                        None
+                    }
+                    Some(Request::Next { speed_ms }) if !live.is_empty() => {
+                        let oid = live.pop_front().unwrap();
+                        Some(Response::Live(repo.get_commit(&oid.to_string()).map(
+                            |metadata| PreparedCommit {
+                                live: true,
+                                ..PreparedCommit::new(metadata, speed_ms)
+                            },
+                        )))
+                    }
+                    Some(Request::Next { speed_ms }) => Some(Response::Next(
+                        selection
+                            .next(&mut repo)
+                            .map(|metadata| PreparedCommit::new(metadata, speed_ms)),
+                    )),
+                    Some(Request::Commit { hash, speed_ms }) => {
+                        let result = repo
+                            .get_commit(&hash)
+                            .map(|metadata| PreparedCommit::new(metadata, speed_ms));
+                        Some(Response::Commit(hash, result))
+                    }
+                    Some(Request::Prepare { metadata, speed_ms }) => Some(Response::Commit(
+                        metadata.hash.clone(),
+                        Ok(PreparedCommit::new(*metadata, speed_ms)),
+                    )),
+                    None => None,
+                };
+                if let Some(response) = response {
+                    if response_tx.send(response).is_err() {
+                        break;
+                    }
+                }
+
+                let Some(speed_ms) = watching else {
+                    continue;
+                };
+                // Unchanged or empty changes keep the request waiting
+                if let Ok(metadata) = selection.next(&mut repo) {
+                    if last_sent.as_ref() != Some(&metadata.hash) {
+                        watching = None;
+                        last_sent = Some(metadata.hash.clone());
+                        let prepared = PreparedCommit::new(metadata, speed_ms);
+                        if response_tx.send(Response::Next(Ok(prepared))).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            requests: request_tx,
+            responses: response_rx,
+            ready: VecDeque::new(),
+            live_ready: VecDeque::new(),
+            pending: 0,
+            live_queued: 0,
+            requested: None,
+            fetched: None,
+        }
+    }
+
+    /// Start preparing the next commit in the background unless one is already on its way
+    pub fn prefetch(&mut self, speed_ms: u64) {
+        self.drain();
+        self.request_if_idle(speed_ms);
+    }
+
+    /// Take the next commit if it is ready, without blocking
+    pub fn try_next(&mut self, speed_ms: u64) -> Option<Result<PreparedCommit>> {
+        self.drain();
+        self.request_if_idle(speed_ms);
+        self.take()
+    }
+
+    /// Whether new commits have landed that were not played yet (follow mode)
+    pub fn live_pending(&mut self) -> bool {
+        self.drain();
+        self.live_queued > 0 || !self.live_ready.is_empty()
+    }
+
+    /// Wait for the next commit
+    pub fn next_blocking(&mut self, speed_ms: u64) -> Result<PreparedCommit> {
+        loop {
+            self.request_if_idle(speed_ms);
+            if let Some(result) = self.take() {
+                return result;
+            }
+            let response = self
+                .responses
+                .recv()
+                .context("Commit prefetch worker stopped")?;
+            self.receive(response);
+        }
+    }
+
+    /// Start loading a specific commit; `try_commit` hands it over once it is ready
+    /// Replaces any earlier request that has not been taken yet
+    pub fn request_commit(&mut self, hash: &str, speed_ms: u64) -> Result<()> {
+        self.send_request(
+            hash,
+            Request::Commit {
+                hash: hash.to_string(),
+                speed_ms,
+            },
+        )
+    }
+
+    /// Like `request_commit` for a commit whose metadata is cached, so only its steps are built
+    pub fn request_prepare(&mut self, metadata: CommitMetadata, speed_ms: u64) -> Result<()> {
+        let hash = metadata.hash.clone();
+        self.send_request(
+            &hash,
+            Request::Prepare {
+                metadata: Box::new(metadata),
+                speed_ms,
+            },
+        )
+    }
+
+    fn send_request(&mut self, hash: &str, request: Request) -> Result<()> {
+        self.requests
+            .send(request)
+            .context("Commit prefetch worker stopped")?;
+        self.requested = Some(hash.to_string());
+        self.fetched = None;
+        Ok(())
+    }
+
+    /// Take the commit asked for with `request_commit` if it is ready, without blocking
+    pub fn try_commit(&mut self) -> Option<Result<PreparedCommit>> {
+        self.drain();
+        self.fetched.take()
+    }
+
+    /// Next commit to play: new commits go first, and none is handed out while
+    /// one that has landed is still on its way
+    fn take(&mut self) -> Option<Result<PreparedCommit>> {
+        if let Some(prepared) = self.live_ready.pop_front() {
+            return Some(Ok(prepared));
+        }
+        if self.live_queued > 0 {
+            return None;
+        }
+        self.ready.pop_front()
+    }
+
+    fn request_if_idle(&mut self, speed_ms: u64) {
+        // New commits are asked for even when a historical one is prepared
+        let wanted = self.live_queued > 0 || (self.ready.is_empty() && self.live_ready.is_empty());
+        if self.pending == 0 && wanted {
+            self.request_next(speed_ms);
+        }
+    }
+
+    /// Receive everything the worker has sent so far
+    fn drain(&mut self) {
+        loop {
+            match self.responses.try_recv() {
+                Ok(response) => self.receive(response),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.live_queued = 0;
+                    if self.pending > 0 {
+                        self.pending = 0;
+                        self.ready
+                            .push_back(Err(anyhow::anyhow!("Commit prefetch worker stopped")));
+                    }
+                    if self.requested.take().is_some() {
+                        self.fetched = Some(Err(anyhow::anyhow!("Commit prefetch worker stopped")));
+                    }
+                    break;
+                }
+            }
+        }
+    }
+
+    fn request_next(&mut self, speed_ms: u64) {
+        if self.requests.send(Request::Next { speed_ms }).is_ok() {
+            self.pending += 1;
+        } else {
+            self.ready
+                .push_back(Err(anyhow::anyhow!("Commit prefetch worker stopped")));
+        }
+    }
+
+    fn receive(&mut self, response: Response) {
+        match response {
+            Response::Next(result) => {
+                self.pending = self.pending.saturating_sub(1);
+                // Historical commits keep their order behind any new ones
+                self.ready.push_back(result);
+            }
+            Response::Live(result) => {
+                self.pending = self.pending.saturating_sub(1);
+                self.live_queued = self.live_queued.saturating_sub(1);
+                // A new commit that can't be read is skipped rather than ending playback
+                if let Ok(prepared) = result {
+                    self.live_ready.push_back(prepared);
+                }
+            }
+            Response::Arrived(count) => self.live_queued += count,
+            Response::Commit(hash, result) => {
+                // Answers to abandoned commit requests are dropped
+                if self.requested.as_ref() == Some(&hash) {
+                    self.requested = None;
+                    self.fetched = Some(result);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fixture::Fixture;
+
+    /// Poll `condition` until it holds, failing the test after a while
+    fn wait_for(mut condition: impl FnMut() -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition() {
+            assert!(Instant::now() < deadline, "timed out");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    #[test]
+    fn test_follow_keeps_prefetched_history() {
+        let fixture = Fixture::new("follow");
+        for i in 0..3 {
+            fixture.commit(
+                &format!("Commit {}", i),
+                &[("file.txt", &format!("{}\n", i))],
+            );
+        }
+        let selection = CommitSelection {
+            order: PlaybackOrder::Asc,
+            commit_spec: None,
+            is_range_mode: false,
+            loop_playback: true,
+            uncommitted: None,
+            watch: false,
+            follow: true,
+        };
+        let mut source = CommitPrefetcher::spawn(fixture.open(), selection);
+        let first = source.next_blocking(10).unwrap();
+        assert_eq!(first.metadata.message, "Commit 0");
+
+        // Commit 1 is prepared ahead, then a new commit lands
+        source.prefetch(10);
+        wait_for(|| {
+            source.drain();
+            !source.ready.is_empty()
+        });
+        fixture.commit("Live", &[("file.txt", "live\n")]);
+        wait_for(|| source.live_pending());
+
+        let mut next = || source.next_blocking(10).unwrap();
+        let live = next();
+        assert!(live.live);
+        assert_eq!(live.metadata.message, "Live");
+        assert_eq!(next().metadata.message, "Commit 1");
+        assert_eq!(next().metadata.message, "Commit 2");
+    }
+
+    #[test]
+    fn test_request_commit_keeps_latest_request() {
+        let fixture = Fixture::new("request-commit");
+        let first = fixture.commit("First", &[("file.txt", "1\n")]);
+        let second = fixture.commit("Second", &[("file.txt", "2\n")]);
+        let selection = CommitSelection {
+            order: PlaybackOrder::Asc,
+            commit_spec: None,
+            is_range_mode: false,
+            loop_playback: false,
+            uncommitted: None,
+            watch: false,
+            follow: false,
+        };
+        let mut source = CommitPrefetcher::spawn(fixture.open(), selection);
+
+        // The answer to the first request arrives but is no longer wanted
+        source.request_commit(&first, 10).unwrap();
+        source.request_commit(&second, 10).unwrap();
+        let mut fetched = None;
+        wait_for(|| {
+            fetched = source.try_commit();
+            fetched.is_some()
+        });
+        assert_eq!(fetched.unwrap().unwrap().metadata.hash, second);
+        assert!(source.try_commit().is_none());
+
+        // Cached metadata only has its steps rebuilt
+        source
+            .request_prepare(fixture.metadata(&first), 10)
+            .unwrap();
+        let mut prepared = None;
+        wait_for(|| {
+            prepared = source.try_commit();
+            prepared.is_some()
+        });
+        let prepared = prepared.unwrap().unwrap();
+        assert_eq!(prepared.metadata.hash, first);
+        assert!(!prepared.steps.is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+use anyhow::{Context, Result};
+use ratatui::backend::{Backend, CrosstermBackend, TestBackend};
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::Terminal;
+use serde_json::json;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::clock::VirtualClock;
+use crate::git::CommitMetadata;
+use crate::theme_switcher::ThemeSwitcher;
+use crate::ui::UI;
+
+// Hide the cursor and clear the screen before the first frame
+const CAST_PREAMBLE: &str = "\x1b[?25l\x1b[2J";
+
+/// Terminal size and playback settings of a recording
+pub struct RecordOptions {
+    pub width: u16,
+    pub height: u16,
+    pub speed_ms: u64,
+    /// Seed for typing variation, for recordings that come out the same every time
+    pub seed: Option<u64>,
+}
+
+/// Replay a commit off-screen and save it to `output` as an asciicast v2 file
+/// Runs on a virtual clock, so it takes far less time than the replay itself
+pub fn record(
+    metadata: CommitMetadata,
+    options: &RecordOptions,
+    themes: ThemeSwitcher,
+    output: &Path,
+) -> Result<()> {
+    let file =
+        File::create(output).with_context(|| format!("Failed to create {}", output.display()))?;
+    let mut writer = CastWriter::new(
+        BufWriter::new(file),
+        options.width,
+        options.height,
+        &replay_title(&metadata),
+    )?;
+
+    let duration = replay_headless(metadata, options, themes, |time, buffer| {
+        writer.frame(time, buffer)
+    })?;
+
+    let frames = writer.frames();
+    writer.finish(duration)?;
+    println!(
+        "Recorded {} frames ({:.1}s) to {}",
+        frames,
+        duration.as_secs_f64(),
+        output.display()
+    );
+    Ok(())
+}
+
+/// Play a commit to the end in an off-screen terminal on a virtual clock
+/// Each redrawn frame is passed to `on_frame`; returns the length of the replay
+pub fn replay_headless(
+    metadata: CommitMetadata,
+    options: &RecordOptions,
+    themes: ThemeSwitcher,
+    mut on_frame: impl FnMut(Duration, &Buffer) -> Result<()>,
+) -> Result<Duration> {
+    let clock = VirtualClock::new();
+    let mut terminal = Terminal::new(TestBackend::new(options.width, options.height))?;
+    let mut ui = UI::headless(options.speed_ms, themes, &clock)?;
+    if let Some(seed) = options.seed {
+        ui.set_seed(seed);
+    }
+    ui.load_commit(metadata);
+    ui.run_headless(&mut terminal, &clock, |time, buffer, _| {
+        on_frame(time, buffer)
+    })?;
+    Ok(clock.elapsed())
+}
+
+/// Title of a recording, e.g. "gitlogue: abc1234 Fix parser"
+pub fn replay_title(metadata: &CommitMetadata) -> String {
+    format!(
+        "gitlogue: {} {}",
+        metadata.short_id(),
+        metadata.message.lines().next().unwrap_or_default()
+    )
+}
+
+/// Writes rendered frames as an asciicast v2 recording
+/// Each frame is stored as the escape sequences that redraw the cells changed since the last one
+pub struct CastWriter<W: Write> {
+    out: W,
+    previous: Buffer,
+    frames: usize,
+    duration: Duration,
+}
+
+impl<W: Write> CastWriter<W> {
+    pub fn new(mut out: W, width: u16, height: u16, title: &str) -> Result<Self> {
+        // No timestamp, so that recordings made with the same --seed are identical
+        let header = json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "title": title,
+            "env": { "TERM": "xterm-256color" },
+        });
+        writeln!(out, "{}", header)?;
+
+        let mut writer = Self {
+            out,
+            previous: Buffer::empty(Rect::new(0, 0, width, height)),
+            frames: 0,
+            duration: Duration::ZERO,
+        };
+        writer.event(Duration::ZERO, CAST_PREAMBLE)?;
+        Ok(writer)
+    }
+
+    /// Record `buffer` as shown at `time`; frames identical to the last one are dropped
+    pub fn frame(&mut self, time: Duration, buffer: &Buffer) -> Result<()> {
+        if buffer.area != self.previous.area {
+            anyhow::bail!("Frame size changed during recording");
+        }
+
+        let updates = self.previous.diff(buffer);
+        if updates.is_empty() {
+            return Ok(());
+        }
+
+        let mut data = Vec::new();
+        CrosstermBackend::new(&mut data).draw(updates.into_iter())?;
+        let data = String::from_utf8(data).context("Rendered frame is not valid UTF-8")?;
+
+        self.event(time, &data)?;
+        self.previous = buffer.clone();
+        self.frames += 1;
+        Ok(())
+    }
+
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// End the recording at `end`, keeping the last frame on screen until then
+    pub fn finish(mut self, end: Duration) -> Result<W> {
+        if end > self.duration {
+            self.event(end, "")?;
+        }
+        self.out.flush()?;
+        Ok(self.out)
+    }
+
+    fn event(&mut self, time: Duration, data: &str) -> Result<()> {
+        let event = json!([(time.as_secs_f64() * 1e6).round() / 1e6, "o", data]);
+        writeln!(self.out, "{}", event)?;
+        self.duration = time;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::{Color, Style};
+
+    fn lines(output: &[u8]) -> Vec<serde_json::Value> {
+        String::from_utf8_lossy(output)
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_cast_writer() {
+        let mut writer = CastWriter::new(Vec::new(), 4, 2, "demo").unwrap();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(0, 1, "hi", Style::default().fg(Color::Indexed(208)));
+
+        writer.frame(Duration::from_millis(1500), &buffer).unwrap();
+        // Unchanged frames are skipped
+        writer.frame(Duration::from_secs(2), &buffer).unwrap();
+        assert_eq!(writer.frames(), 1);
+
+        let events = lines(&writer.finish(Duration::from_secs(3)).unwrap());
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0]["version"], 2);
+        assert_eq!(events[0]["width"], 4);
+        assert_eq!(events[0]["height"], 2);
+        assert_eq!(events[1][2], CAST_PREAMBLE);
+
+        assert_eq!(events[2][0], 1.5);
+        assert_eq!(events[2][1], "o");
+        let data = events[2][2].as_str().unwrap();
+        // Cursor moves to row 2, column 1 before drawing in orange
+        assert!(data.starts_with("\x1b[2;1H"));
+        assert!(data.contains("hi"));
+        assert!(data.contains("38;5;208"));
+
+        // The recording lasts until the end even without further changes
+        assert_eq!(events[3][0], 3.0);
+        assert_eq!(events[3][2], "");
+    }
+
+    #[test]
+    fn test_cast_writer_rejects_resized_frames() {
+        let mut writer = CastWriter::new(Vec::new(), 4, 2, "demo").unwrap();
+        let buffer = Buffer::empty(Rect::new(0, 0, 5, 2));
+        assert!(writer.frame(Duration::ZERO, &buffer).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+use super::languages::{self, LanguageInfo};
+use anyhow::{Context, Result};
+use globset::{Glob, GlobMatcher};
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::OnceLock;
+
+// Vim reads modelines from the first and last 5 lines of a file
+const MODELINE_LINES: usize = 5;
+
+// Language mappings from the config file
+static USER_MAPPINGS: OnceLock<LanguageMappings> = OnceLock::new();
+
+/// Languages for files matched by the `[languages]` config table
+#[derive(Default)]
+struct LanguageMappings {
+    /// Extensions (without the dot) and exact file names
+    names: HashMap<String, &'static LanguageInfo>,
+    globs: Vec<(GlobMatcher, &'static LanguageInfo)>,
+}
+
+impl LanguageMappings {
+    fn new(mappings: &BTreeMap<String, String>) -> Result<Self> {
+        let mut result = Self::default();
+        for (pattern, name) in mappings {
+            let info = languages::by_name(name).with_context(|| {
+                format!(
+                    "Unknown language '{}' for '{}' (see `gitlogue languages`)",
+                    name, pattern
+                )
+            })?;
+            if pattern.contains(['*', '?', '[', '{', '/']) {
+                let glob = Glob::new(pattern)
+                    .with_context(|| format!("Invalid glob pattern: {}", pattern))?;
+                result.globs.push((glob.compile_matcher(), info));
+            } else {
+                let name = pattern.strip_prefix('.').unwrap_or(pattern);
+                result.names.insert(name.to_string(), info);
+            }
+        }
+        Ok(result)
+    }
+
+    // Extensions and file names are more specific than globs, so they are checked first
+    fn language_for(&self, path: &Path) -> Option<&'static LanguageInfo> {
+        let by_name = |name: Option<&std::ffi::OsStr>| self.names.get(name?.to_str()?).copied();
+        by_name(path.file_name())
+            .or_else(|| by_name(path.extension()))
+            .or_else(|| {
+                self.globs
+                    .iter()
+                    .find(|(glob, _)| glob.is_match(path))
+                    .map(|(_, info)| *info)
+            })
+    }
+}
+
+/// Initialize the user-defined `[languages]` mappings (call once at startup)
+///
+/// Keys are extensions (`inc` or `.inc`), file names (`Jenkinsfile`) or globs
+/// (`*.tpl`, `templates/**/*.txt`); values are language names or aliases.
+pub fn init_language_mappings(mappings: &BTreeMap<String, String>) -> Result<()> {
+    if mappings.is_empty() {
+        return Ok(());
+    }
+
+    USER_MAPPINGS
+        .set(LanguageMappings::new(mappings)?)
+        .map_err(|_| anyhow::anyhow!("Language mappings already initialized"))
+}
+
+/// Detect the language of a file, trying in order: a `.gitattributes`
+/// `linguist-language` override, the `[languages]` config mappings, a Vim or
+/// Emacs modeline, the exact file name, the shebang interpreter, and finally
+/// the extension
+pub fn detect_language(
+    path: &Path,
+    content: &str,
+    linguist_language: Option<&str>,
+) -> Option<&'static LanguageInfo> {
+    linguist_language
+        .and_then(languages::by_name)
+        .or_else(|| USER_MAPPINGS.get()?.language_for(path))
+        .or_else(|| modeline_language(content))
+        .or_else(|| languages::by_filename(path.file_name()?.to_str()?))
+        .or_else(|| shebang_language(content))
+        .or_else(|| languages::by_extension(path.extension()?.to_str()?))
+}
+
+fn modeline_language(content: &str) -> Option<&'static LanguageInfo> {
+    let lines: Vec<&str> = content.lines().collect();
+    let tail_start = lines
+        .len()
+        .saturating_sub(MODELINE_LINES)
+        .max(MODELINE_LINES);
+    lines
+        .iter()
+        .take(MODELINE_LINES)
+        .chain(lines.iter().skip(tail_start))
+        .find_map(|line| vim_modeline(line).or_else(|| emacs_modeline(line)))
+        .and_then(languages::by_name)
+}
+
+/// `vim: set ft=python:`, `vi:syntax=ruby`, `vim600: filetype=sh`
+fn vim_modeline(line: &str) -> Option<&str> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(
+            r"(?:^|\s)(?:vim?|Vim|ex)(?:[<=>]?\d+)?:(?:.*?[\s:])?(?:ft|filetype|syntax)\s*=\s*([\w+.-]+)",
+        )
+        .unwrap()
+    });
+    Some(pattern.captures(line)?.get(1)?.as_str())
+}
+
+/// `-*- mode: ruby; coding: utf-8 -*-` or `-*- python -*-`
+fn emacs_modeline(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("-*-")?;
+    let (variables, _) = rest.split_once("-*-")?;
+
+    let variables: Vec<&str> = variables.split(';').map(str::trim).collect();
+    if let [mode] = variables[..] {
+        if !mode.contains(':') && !mode.is_empty() {
+            return Some(mode);
+        }
+    }
+    variables.iter().find_map(|variable| {
+        let (name, value) = variable.split_once(':')?;
+        (name.trim().eq_ignore_ascii_case("mode")).then(|| value.trim())
+    })
+}
+
+/// `#!/bin/sh`, `#!/usr/bin/env python3`, `#!/usr/bin/env -S deno run`
+fn shebang_language(content: &str) -> Option<&'static LanguageInfo> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip env options and variable assignments
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    // python3.12 -> python
+    let interpreter = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+    languages::by_interpreter(interpreter)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(path: &str, content: &str) -> Option<&'static str> {
+        detect_language(Path::new(path), content, None).map(|info| info.name)
+    }
+
+    #[test]
+    fn test_filenames() {
+        assert_eq!(detect("pkg/PKGBUILD", ""), Some("bash"));
+        assert_eq!(detect("BUILD.bazel", ""), Some("python"));
+        assert_eq!(detect("Vagrantfile", ""), Some("ruby"));
+        assert_eq!(detect("Rakefile", ""), Some("ruby"));
+        assert_eq!(detect("app/Gemfile", ""), Some("ruby"));
+        assert_eq!(detect("home/.bashrc", ""), Some("bash"));
+        assert_eq!(detect("src/main.rs", ""), Some("rust"));
+        assert_eq!(detect("README", ""), None);
+    }
+
+    #[test]
+    fn test_shebangs() {
+        assert_eq!(
+            detect("bin/run", "#!/usr/bin/env python3\n"),
+            Some("python")
+        );
+        assert_eq!(
+            detect("bin/run", "#!/usr/bin/python3.12 -u\n"),
+            Some("python")
+        );
+        assert_eq!(detect("bin/run", "#! /bin/sh\nset -e\n"), Some("bash"));
+        assert_eq!(
+            detect("bin/run", "#!/usr/bin/env -S deno run\n"),
+            Some("typescript")
+        );
+        assert_eq!(
+            detect("bin/run", "#!/usr/bin/env FOO=1 ruby\n"),
+            Some("ruby")
+        );
+        assert_eq!(detect("bin/run", "#!/usr/bin/env perl\n"), None);
+        // The shebang wins over a misleading extension
+        assert_eq!(detect("deploy.txt", "#!/bin/bash\n"), Some("bash"));
+    }
+
+    #[test]
+    fn test_modelines() {
+        assert_eq!(
+            detect("conf/app", "# vim: set ft=python:\n"),
+            Some("python")
+        );
+        assert_eq!(
+            detect("conf/app", "x = 1\n# vi:syntax=ruby\n"),
+            Some("ruby")
+        );
+        assert_eq!(detect("conf/app", "/* vim: filetype=cpp */\n"), Some("cpp"));
+        assert_eq!(
+            detect("conf/app", "# -*- mode: ruby; coding: utf-8 -*-\n"),
+            Some("ruby")
+        );
+        assert_eq!(detect("conf/app", "# -*- shell-script -*-\n"), Some("bash"));
+        assert_eq!(detect("conf/app", "# -*- coding: utf-8 -*-\n"), None);
+
+        // Only the first and last lines are searched
+        let middle = format!("{}# vim: ft=ruby\n{}", "\n".repeat(6), "\n".repeat(6));
+        assert_eq!(detect("conf/app", &middle), None);
+        let last = format!("{}# vim: ft=ruby\n", "\n".repeat(20));
+        assert_eq!(detect("conf/app", &last), Some("ruby"));
+
+        // A modeline beats the file name and the shebang
+        assert_eq!(detect("Makefile", "# vim: ft=python\n"), Some("python"));
+        assert_eq!(
+            detect("run", "#!/bin/sh\n# -*- mode: ruby -*-\n"),
+            Some("ruby")
+        );
+    }
+
+    #[test]
+    fn test_linguist_language_overrides_everything() {
+        let detect = |path: &str, content: &str, linguist: &str| {
+            detect_language(Path::new(path), content, Some(linguist)).map(|info| info.name)
+        };
+        assert_eq!(detect("lib/x.inc", "", "PHP"), Some("php"));
+        assert_eq!(detect("x.rs", "# vim: ft=python\n", "Shell"), Some("bash"));
+        assert_eq!(
+            detect("tsconfig.json", "", "JSON-with-Comments"),
+            Some("json")
+        );
+        assert_eq!(detect("x.h", "", "C++"), Some("cpp"));
+        // Unknown names fall back to the other rules
+        assert_eq!(detect("x.rs", "", "Brainfuck"), Some("rust"));
+    }
+
+    #[test]
+    fn test_language_mappings() {
+        let mappings: BTreeMap<String, String> = [
+            ("*.tpl", "HTML"),
+            (".inc", "php"),
+            ("jsonl", "json"),
+            ("Jenkinsfile", "sh"),
+            ("templates/**/*.txt", "html"),
+        ]
+        .into_iter()
+        .map(|(pattern, name)| (pattern.to_string(), name.to_string()))
+        .collect();
+        let mappings = LanguageMappings::new(&mappings).unwrap();
+        let language = |path: &str| mappings.language_for(Path::new(path)).map(|info| info.name);
+
+        assert_eq!(language("views/index.tpl"), Some("html"));
+        assert_eq!(language("lib/db.inc"), Some("php"));
+        assert_eq!(language("events.jsonl"), Some("json"));
+        assert_eq!(language("ci/Jenkinsfile"), Some("bash"));
+        assert_eq!(language("templates/mail/welcome.txt"), Some("html"));
+        assert_eq!(language("notes.txt"), None);
+
+        let unknown = BTreeMap::from([("*.x".to_string(), "cobol".to_string())]);
+        let error = LanguageMappings::new(&unknown).err().unwrap();
+        assert!(error
+            .to_string()
+            .starts_with("Unknown language 'cobol' for '*.x'"));
+    }
+}
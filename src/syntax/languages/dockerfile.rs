@@ -0,0 +1,119 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_containerfile::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on tree_sitter_containerfile::HIGHLIGHTS_QUERY, with image names, stage aliases
+// and variables picked out from the instruction arguments
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+
+[
+  "FROM"
+  "AS"
+  "RUN"
+  "CMD"
+  "LABEL"
+  "EXPOSE"
+  "ENV"
+  "ADD"
+  "COPY"
+  "ENTRYPOINT"
+  "VOLUME"
+  "USER"
+  "WORKDIR"
+  "ARG"
+  "ONBUILD"
+  "STOPSIGNAL"
+  "HEALTHCHECK"
+  "SHELL"
+  "MAINTAINER"
+  "CROSS_BUILD"
+] @keyword
+
+; Images and build stages
+(image_spec
+  (image_tag
+    ":" @punctuation.special))
+
+(image_spec
+  (image_digest
+    "@" @punctuation.special))
+
+(image_name) @type
+(image_tag) @constant
+(image_digest) @constant
+(image_alias) @label
+
+; Options and key/value pairs
+(param
+  "--" @operator
+  name: (_) @property)
+
+(mount_param
+  "--" @operator
+  name: (_) @property)
+
+(mount_param_param) @property
+
+(arg_pair
+  name: (unquoted_string) @property)
+
+(env_pair
+  name: (unquoted_string) @property)
+
+(label_pair
+  key: (_) @property)
+
+; Variables
+(expansion
+  [
+    "$"
+    "{"
+    "}"
+  ] @punctuation.special)
+
+(expansion_operator) @operator
+
+(variable) @variable
+
+; Literals
+(escape_sequence) @string.escape
+
+[
+  (double_quoted_string)
+  (single_quoted_string)
+  (json_string)
+] @string
+
+(heredoc_block) @string
+
+[
+  (heredoc_marker)
+  (heredoc_end)
+] @label
+
+(expose_port) @number
+
+(json_string_array
+  [
+    "["
+    "]"
+  ] @punctuation.bracket)
+
+(json_string_array
+  "," @punctuation.delimiter)
+
+(line_continuation) @operator
+"#;
+
+// Shell commands of RUN, CMD and friends; each one is its own script
+pub const INJECTION_QUERY: &str = r#"
+((shell_command) @injection.content
+  (#set! injection.language "bash"))
+
+((run_instruction
+  (heredoc_block
+    (heredoc_content) @injection.content))
+  (#set! injection.language "bash"))
+"#;
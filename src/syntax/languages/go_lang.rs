@@ -3,3 +3,17 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_go::HIGHLIGHTS_QUERY;
+
+// SQL in string literals
+pub const INJECTION_QUERY: &str = concat!(
+    r#"
+([
+  (interpreted_string_literal_content)
+  (raw_string_literal_content)
+] @injection.content
+  "#,
+    sql_string_predicate!(),
+    r#"
+  (#set! injection.language "sql"))
+"#
+);
@@ -0,0 +1,124 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_graphql::LANGUAGE.into()
+}
+
+// Custom highlight query (the grammar crate ships none)
+// Based on nvim-treesitter's GraphQL query: type names as types, fields as
+// properties, variables and arguments as parameters
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+(description) @comment
+
+[
+  "query"
+  "mutation"
+  "subscription"
+  "fragment"
+  "on"
+  "type"
+  "interface"
+  "union"
+  "enum"
+  "input"
+  "scalar"
+  "schema"
+  "extend"
+  "directive"
+  "implements"
+  "repeatable"
+] @keyword
+
+(operation_type) @keyword
+
+; Types
+(named_type
+  (name) @type)
+
+(type_condition
+  (named_type
+    (name) @type))
+
+[
+  (object_type_definition (name) @type)
+  (interface_type_definition (name) @type)
+  (union_type_definition (name) @type)
+  (enum_type_definition (name) @type)
+  (input_object_type_definition (name) @type)
+  (scalar_type_definition (name) @type)
+]
+
+; Operations and fragments
+(operation_definition
+  (name) @function)
+
+(fragment_name
+  (name) @function)
+
+; Directives
+(directive
+  "@" @attribute
+  (name) @attribute)
+
+(directive_definition
+  "@" @attribute
+  (name) @attribute)
+
+[
+  (directive_location)
+  (executable_directive_location)
+  (type_system_directive_location)
+] @constant
+
+; Fields and arguments
+(alias
+  (name) @label)
+
+(field
+  (name) @property)
+
+(field_definition
+  (name) @property)
+
+(object_field
+  (name) @property)
+
+(argument
+  (name) @parameter)
+
+(input_value_definition
+  (name) @parameter)
+
+(variable) @variable
+
+(enum_value) @constant
+
+; Literals
+(string_value) @string
+(int_value) @number
+(float_value) @number
+(boolean_value) @boolean
+(null_value) @constant
+
+; Punctuation
+[
+  "="
+  "|"
+  "&"
+  "!"
+  "..."
+] @operator
+
+[
+  ":"
+  (comma)
+] @punctuation.delimiter
+
+[
+  "{"
+  "}"
+  "("
+  ")"
+  "["
+  "]"
+] @punctuation.bracket
+"#;
@@ -0,0 +1,120 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_hcl::LANGUAGE.into()
+}
+
+// Custom highlight query (the grammar crate ships none)
+// Based on nvim-treesitter's HCL query: block types as keywords, block labels as
+// types, attribute names as properties
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+
+; Blocks and attributes
+(block
+  (identifier) @keyword)
+
+(block
+  (string_lit) @type)
+
+(attribute
+  (identifier) @property)
+
+(object_elem
+  key: (expression
+    (variable_expr
+      (identifier) @property)))
+
+; Functions
+(function_call
+  (identifier) @function)
+
+; Variables (var.name, local.name, resource.name.attr)
+(get_attr
+  (identifier) @property)
+
+((variable_expr
+  (identifier) @variable.builtin)
+  (#match? @variable.builtin "^(var|local|module|data|path|count|each|self|terraform)$"))
+
+(variable_expr
+  (identifier) @variable)
+
+(for_intro
+  (identifier) @variable)
+
+; Literals
+(bool_lit) @boolean
+(null_lit) @constant
+(numeric_lit) @number
+
+[
+  (template_interpolation_start)
+  (template_interpolation_end)
+  (template_directive_start)
+  (template_directive_end)
+  (strip_marker)
+] @punctuation.special
+
+[
+  "if"
+  "else"
+  "endif"
+  "for"
+  "endfor"
+  "in"
+] @keyword
+
+[
+  (heredoc_identifier)
+  (heredoc_start)
+] @label
+
+[
+  (quoted_template_start)
+  (quoted_template_end)
+  (template_literal)
+] @string
+
+; Operators
+[
+  "!"
+  "*"
+  "/"
+  "%"
+  "+"
+  "-"
+  ">"
+  ">="
+  "<"
+  "<="
+  "=="
+  "!="
+  "&&"
+  "||"
+  "="
+  "=>"
+  "?"
+  ":"
+  (ellipsis)
+] @operator
+
+; Punctuation
+[
+  "."
+  ".*"
+  ","
+  "[*]"
+] @punctuation.delimiter
+
+[
+  (block_start)
+  (block_end)
+  (object_start)
+  (object_end)
+  (tuple_start)
+  (tuple_end)
+  "["
+  "]"
+  "("
+  ")"
+] @punctuation.bracket
+"#;
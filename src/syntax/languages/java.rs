@@ -3,3 +3,17 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_java::HIGHLIGHTS_QUERY;
+
+// SQL in string literals and text blocks
+pub const INJECTION_QUERY: &str = concat!(
+    r#"
+([
+  (string_fragment)
+  (multiline_string_fragment)
+] @injection.content
+  "#,
+    sql_string_predicate!(),
+    r#"
+  (#set! injection.language "sql"))
+"#
+);
@@ -3,3 +3,24 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_javascript::HIGHLIGHT_QUERY;
+
+// Tagged templates (html`...`, css`...`, sql`...`, gql`...`) and SQL strings
+pub const INJECTION_QUERY: &str = concat!(
+    r#"
+(call_expression
+  function: [
+    (identifier) @injection.language
+    (member_expression
+      property: (property_identifier) @injection.language)
+  ]
+  arguments: (template_string
+    (string_fragment) @injection.content)
+  (#set! injection.combined))
+
+((string_fragment) @injection.content
+  "#,
+    sql_string_predicate!(),
+    r#"
+  (#set! injection.language "sql"))
+"#
+);
@@ -0,0 +1,139 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_lua::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on tree_sitter_lua::HIGHLIGHTS_QUERY, reordered so that function names,
+// fields and constants win over the plain identifier rule
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+(hash_bang_line) @comment
+
+; Keywords
+[
+  "return"
+  "goto"
+  "in"
+  "local"
+  "global"
+  "function"
+  "do"
+  "end"
+  "while"
+  "repeat"
+  "until"
+  "if"
+  "elseif"
+  "else"
+  "then"
+  "for"
+] @keyword
+
+(break_statement) @keyword
+
+[
+  "and"
+  "not"
+  "or"
+] @keyword.operator
+
+(label_statement) @label
+
+; Functions
+(function_declaration
+  name: [
+    (identifier) @function
+    (dot_index_expression
+      field: (identifier) @function)
+    (method_index_expression
+      method: (identifier) @function.method)
+  ])
+
+(assignment_statement
+  (variable_list
+    .
+    name: [
+      (identifier) @function
+      (dot_index_expression
+        field: (identifier) @function)
+    ])
+  (expression_list
+    .
+    value: (function_definition)))
+
+(field
+  name: (identifier) @function
+  value: (function_definition))
+
+(function_call
+  name: [
+    (identifier) @function.call
+    (dot_index_expression
+      field: (identifier) @function.call)
+    (method_index_expression
+      method: (identifier) @function.method)
+  ])
+
+(parameters
+  (identifier) @parameter)
+
+; Tables
+(field
+  name: (identifier) @property)
+
+(dot_index_expression
+  field: (identifier) @property)
+
+(variable_list
+  (attribute
+    (identifier) @attribute))
+
+; Constants
+((identifier) @variable.builtin
+  (#eq? @variable.builtin "self"))
+
+((identifier) @constant
+  (#match? @constant "^[A-Z][A-Z_0-9]*$"))
+
+(vararg_expression) @constant
+(nil) @constant
+
+[
+  (false)
+  (true)
+] @boolean
+
+(identifier) @variable
+
+; Literals
+(number) @number
+(escape_sequence) @string.escape
+(string) @string
+
+; Operators
+(binary_expression
+  operator: _ @operator)
+
+(unary_expression
+  operator: _ @operator)
+
+"=" @operator
+
+; Punctuation
+[
+  ";"
+  ":"
+  "::"
+  ","
+  "."
+] @punctuation.delimiter
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+"#;
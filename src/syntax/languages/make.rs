@@ -0,0 +1,113 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_make::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on tree_sitter_make::HIGHLIGHTS_QUERY, with targets as functions and
+// variables as constants so rules stand out from recipe text
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+
+; Directives
+[
+  "ifeq"
+  "ifneq"
+  "ifdef"
+  "ifndef"
+  "else"
+  "endif"
+  "define"
+  "endef"
+  "vpath"
+  "undefine"
+  "export"
+  "unexport"
+  "override"
+  "private"
+  "include"
+  "sinclude"
+  "-include"
+] @keyword
+
+; Functions
+(function_call
+  function: _ @function.builtin)
+
+(shell_function
+  "shell" @function.builtin)
+
+; Rules
+(targets
+  (word) @keyword.special
+  (#match? @keyword.special "^\\.(PHONY|SUFFIXES|DEFAULT|PRECIOUS|INTERMEDIATE|SECONDARY|SECONDEXPANSION|DELETE_ON_ERROR|IGNORE|LOW_RESOLUTION_TIME|SILENT|EXPORT_ALL_VARIABLES|NOTPARALLEL|ONESHELL|POSIX)$"))
+
+(targets
+  (word) @function)
+
+(prerequisites
+  (word) @variable.parameter)
+
+; Variables
+(variable_assignment
+  name: (word) @constant)
+
+(variable_reference
+  (word) @constant)
+
+(substitution_reference
+  (word) @constant)
+
+(automatic_variable) @constant.builtin
+
+[
+  "VPATH"
+  ".RECIPEPREFIX"
+] @constant.builtin
+
+[
+  "$"
+  "$$"
+] @punctuation.special
+
+; Operators
+[
+  "="
+  ":="
+  "::="
+  "?="
+  "+="
+  "!="
+  "@"
+  "-"
+  "+"
+] @operator
+
+; Punctuation
+[
+  "("
+  ")"
+  "{"
+  "}"
+] @punctuation.bracket
+
+[
+  ":"
+  "&:"
+  "::"
+  "|"
+  ";"
+  ","
+] @punctuation.delimiter
+
+[
+  (string)
+  (raw_text)
+] @string
+"#;
+
+// Recipe lines are shell commands
+pub const INJECTION_QUERY: &str = r#"
+((recipe_line
+  (shell_text) @injection.content)
+  (#set! injection.language "bash"))
+"#;
@@ -58,3 +58,20 @@ pub const HIGHLIGHT_QUERY: &str = r#"
   (backslash_escape)
 ] @string.escape
 "#;
+
+// Fenced code blocks are highlighted with the language named in their info string
+pub const INJECTION_QUERY: &str = r#"
+(fenced_code_block
+  (info_string
+    (language) @injection.language)
+  (code_fence_content) @injection.content)
+
+((html_block) @injection.content
+  (#set! injection.language "html"))
+
+((minus_metadata) @injection.content
+  (#set! injection.language "yaml"))
+
+((plus_metadata) @injection.content
+  (#set! injection.language "toml"))
+"#;
@@ -1,3 +1,11 @@
+/// `#match?` predicate for string literals holding SQL (`SELECT ...`, `select ...`),
+/// shared by the injection queries of the languages that embed it
+macro_rules! sql_string_predicate {
+    () => {
+        r#"(#match? @injection.content "(?i)^\\s*(SELECT|INSERT|UPDATE|DELETE|CREATE|ALTER|DROP|WITH)\\s")"#
+    };
+}
+
 pub mod bash;
 pub mod c;
 pub mod clojure;
@@ -5,23 +13,45 @@ pub mod cpp;
 pub mod csharp;
 pub mod css;
 pub mod dart;
+#[cfg(feature = "lang-dockerfile")]
+pub mod dockerfile;
 pub mod elixir;
 pub mod erlang;
 pub mod go_lang;
+#[cfg(feature = "lang-graphql")]
+pub mod graphql;
 pub mod haskell;
+#[cfg(feature = "lang-hcl")]
+pub mod hcl;
 pub mod html;
 pub mod java;
 pub mod javascript;
 pub mod json;
 pub mod kotlin;
+#[cfg(feature = "lang-lua")]
+pub mod lua;
+#[cfg(feature = "lang-make")]
+pub mod make;
 pub mod markdown;
+#[cfg(feature = "lang-nix")]
+pub mod nix;
 pub mod php;
+#[cfg(feature = "lang-protobuf")]
+pub mod protobuf;
 pub mod python;
 pub mod ruby;
 pub mod rust;
 pub mod scala;
+#[cfg(feature = "lang-sql")]
+pub mod sql;
+#[cfg(feature = "lang-svelte")]
+pub mod svelte;
 pub mod swift;
+#[cfg(feature = "lang-toml")]
+pub mod toml;
 pub mod typescript;
+#[cfg(feature = "lang-vue")]
+pub mod vue;
 pub mod xml;
 pub mod yaml;
 pub mod zig;
@@ -29,39 +59,428 @@ pub mod zig;
 use std::path::Path;
 use tree_sitter::Language;
 
+/// A supported language and the ways a file is recognized as it
+pub struct LanguageInfo {
+    /// Canonical name, as used in modelines, `.gitattributes` and the config
+    pub name: &'static str,
+    /// Other names for the language (Vim filetypes, Emacs modes, GitHub Linguist names)
+    pub aliases: &'static [&'static str],
+    pub extensions: &'static [&'static str],
+    /// Exact file names; a trailing `*` matches any suffix (e.g. `Dockerfile.*`)
+    pub filenames: &'static [&'static str],
+    /// Shebang interpreters, without version suffixes
+    pub interpreters: &'static [&'static str],
+    language: fn() -> Language,
+    pub highlight_query: &'static str,
+    /// Tree-sitter injection query for embedded languages (empty if none)
+    pub injection_query: &'static str,
+}
+
+impl LanguageInfo {
+    pub fn language(&self) -> Language {
+        (self.language)()
+    }
+
+    fn matches_filename(&self, file_name: &str) -> bool {
+        self.filenames
+            .iter()
+            .any(|pattern| match pattern.strip_suffix('*') {
+                Some(prefix) => file_name.starts_with(prefix),
+                None => file_name == *pattern,
+            })
+    }
+}
+
+macro_rules! language {
+    ($module:ident, $name:literal, aliases: $aliases:expr, extensions: $extensions:expr, filenames: $filenames:expr, interpreters: $interpreters:expr $(,)?) => {
+        language!($module, $name,
+            aliases: $aliases,
+            extensions: $extensions,
+            filenames: $filenames,
+            interpreters: $interpreters,
+            injections: "",
+        )
+    };
+    ($module:ident, $name:literal, aliases: $aliases:expr, extensions: $extensions:expr, filenames: $filenames:expr, interpreters: $interpreters:expr, injections: $injections:expr $(,)?) => {
+        LanguageInfo {
+            name: $name,
+            aliases: &$aliases,
+            extensions: &$extensions,
+            filenames: &$filenames,
+            interpreters: &$interpreters,
+            language: $module::language,
+            highlight_query: $module::HIGHLIGHT_QUERY,
+            injection_query: $injections,
+        }
+    };
+}
+
+pub static LANGUAGES: &[LanguageInfo] = &[
+    language!(rust, "rust",
+        aliases: ["rs"],
+        extensions: ["rs"],
+        filenames: [],
+        interpreters: ["rust-script", "cargo"],
+        injections: rust::INJECTION_QUERY,
+    ),
+    language!(typescript, "typescript",
+        aliases: ["ts", "tsx", "typescriptreact"],
+        extensions: ["ts", "tsx", "mts", "cts"],
+        filenames: [],
+        interpreters: ["deno", "ts-node", "tsx"],
+        injections: typescript::INJECTION_QUERY,
+    ),
+    language!(javascript, "javascript",
+        aliases: ["js", "jsx", "js2", "javascriptreact", "node"],
+        extensions: ["js", "jsx", "mjs", "cjs"],
+        filenames: [],
+        interpreters: ["node", "nodejs", "bun", "qjs"],
+        injections: javascript::INJECTION_QUERY,
+    ),
+    language!(python, "python",
+        aliases: ["py", "starlark", "bazel"],
+        extensions: ["py", "pyw", "bzl"],
+        filenames: ["BUILD", "BUILD.bazel", "WORKSPACE", "SConstruct", "SConscript", "Snakefile", "Tiltfile"],
+        interpreters: ["python", "pypy"],
+        injections: python::INJECTION_QUERY,
+    ),
+    #[cfg(feature = "lang-toml")]
+    language!(toml, "toml",
+        aliases: [],
+        extensions: ["toml"],
+        filenames: ["Pipfile", "Cargo.lock", "uv.lock", "poetry.lock"],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-sql")]
+    language!(sql, "sql",
+        aliases: ["pgsql", "postgresql", "postgres", "mysql", "plsql", "sqlite"],
+        extensions: ["sql", "pgsql", "psql", "mysql"],
+        filenames: [],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-lua")]
+    language!(lua, "lua",
+        aliases: ["luau"],
+        extensions: ["lua", "rockspec"],
+        filenames: [".luacheckrc"],
+        interpreters: ["lua", "luajit"],
+    ),
+    // Dockerfile and Makefile come before bash, which covers them when their features are off
+    #[cfg(feature = "lang-dockerfile")]
+    language!(dockerfile, "dockerfile",
+        aliases: ["containerfile", "docker"],
+        extensions: ["dockerfile", "containerfile"],
+        filenames: ["Dockerfile", "Dockerfile.*", "Containerfile", "Containerfile.*"],
+        interpreters: [],
+        injections: dockerfile::INJECTION_QUERY,
+    ),
+    #[cfg(feature = "lang-make")]
+    language!(make, "make",
+        aliases: ["makefile", "gnumake", "bsdmake"],
+        extensions: ["mk", "mak", "make"],
+        filenames: ["Makefile", "makefile", "GNUmakefile", "Makefile.*"],
+        interpreters: ["make"],
+        injections: make::INJECTION_QUERY,
+    ),
+    // Dockerfiles and Makefiles are mostly shell commands, comments and variables
+    language!(bash, "bash",
+        aliases: ["sh", "shell", "shell-script", "shellscript", "zsh", "ksh", "dockerfile", "make", "makefile"],
+        extensions: ["sh", "bash", "zsh", "ksh", "dockerfile", "mk", "mak"],
+        filenames: [
+            ".bashrc", ".bash_profile", ".bash_aliases", ".bash_logout", ".profile",
+            ".zshrc", ".zshenv", ".zprofile", ".zlogin", ".zlogout", ".envrc",
+            "PKGBUILD", "APKBUILD", "Dockerfile", "Dockerfile.*", "Containerfile",
+            "Makefile", "makefile", "GNUmakefile",
+        ],
+        interpreters: ["sh", "bash", "zsh", "ksh", "dash", "ash", "mksh"],
+    ),
+    language!(go_lang, "go",
+        aliases: ["golang"],
+        extensions: ["go"],
+        filenames: [],
+        interpreters: [],
+        injections: go_lang::INJECTION_QUERY,
+    ),
+    language!(ruby, "ruby",
+        aliases: ["rb"],
+        extensions: ["rb", "rbw", "rake", "gemspec"],
+        filenames: [
+            "Rakefile", "Gemfile", "Guardfile", "Podfile", "Vagrantfile", "Brewfile",
+            "Fastfile", "Appfile", "Dangerfile", "Capfile", "Berksfile", "Thorfile", "config.ru",
+        ],
+        interpreters: ["ruby", "jruby", "rake"],
+        injections: ruby::INJECTION_QUERY,
+    ),
+    language!(swift, "swift",
+        aliases: [],
+        extensions: ["swift"],
+        filenames: [],
+        interpreters: ["swift"],
+    ),
+    language!(kotlin, "kotlin",
+        aliases: ["kt"],
+        extensions: ["kt", "kts"],
+        filenames: [],
+        interpreters: ["kotlin", "kscript"],
+        injections: kotlin::INJECTION_QUERY,
+    ),
+    language!(java, "java",
+        aliases: [],
+        extensions: ["java"],
+        filenames: [],
+        interpreters: [],
+        injections: java::INJECTION_QUERY,
+    ),
+    language!(php, "php",
+        aliases: [],
+        extensions: ["php", "php3", "php4", "php5", "phtml"],
+        filenames: [],
+        interpreters: ["php"],
+        injections: php::INJECTION_QUERY,
+    ),
+    language!(csharp, "csharp",
+        aliases: ["c#", "cs"],
+        extensions: ["cs", "csx"],
+        filenames: [],
+        interpreters: [],
+    ),
+    // C++ before C to handle .h files (can be either)
+    language!(cpp, "cpp",
+        aliases: ["c++", "cxx"],
+        extensions: ["cpp", "cc", "cxx", "c++", "C", "CPP", "hpp", "hh", "hxx", "h++", "H", "HPP", "tcc", "inl"],
+        filenames: [],
+        interpreters: [],
+    ),
+    language!(c, "c",
+        aliases: [],
+        extensions: ["c", "h"],
+        filenames: [],
+        interpreters: [],
+    ),
+    language!(haskell, "haskell",
+        aliases: ["hs"],
+        extensions: ["hs", "lhs"],
+        filenames: [],
+        interpreters: ["runhaskell", "runghc"],
+    ),
+    language!(dart, "dart",
+        aliases: [],
+        extensions: ["dart"],
+        filenames: [],
+        interpreters: ["dart"],
+    ),
+    language!(scala, "scala",
+        aliases: [],
+        extensions: ["scala", "sc", "sbt"],
+        filenames: [],
+        interpreters: ["scala", "amm"],
+    ),
+    language!(clojure, "clojure",
+        aliases: ["clj"],
+        extensions: ["clj", "cljs", "cljc", "edn"],
+        filenames: [],
+        interpreters: ["clojure", "bb"],
+    ),
+    language!(zig, "zig",
+        aliases: [],
+        extensions: ["zig"],
+        filenames: [],
+        interpreters: [],
+    ),
+    language!(elixir, "elixir",
+        aliases: ["ex"],
+        extensions: ["ex", "exs"],
+        filenames: [],
+        interpreters: ["elixir"],
+    ),
+    language!(erlang, "erlang",
+        aliases: ["erl"],
+        extensions: ["erl", "hrl", "es", "escript"],
+        filenames: ["rebar.config", "Emakefile"],
+        interpreters: ["escript"],
+    ),
+    language!(html, "html",
+        aliases: ["xhtml"],
+        extensions: ["html", "htm"],
+        filenames: [],
+        interpreters: [],
+        injections: html::INJECTION_QUERY,
+    ),
+    language!(css, "css",
+        aliases: ["scss", "sass"],
+        extensions: ["css", "scss", "sass"],
+        filenames: [],
+        interpreters: [],
+    ),
+    language!(json, "json",
+        aliases: ["jsonc", "json with comments"],
+        extensions: ["json", "jsonc"],
+        filenames: [".babelrc", ".eslintrc", ".jshintrc", ".swcrc"],
+        interpreters: [],
+    ),
+    language!(markdown, "markdown",
+        aliases: ["md", "gfm"],
+        extensions: ["md", "markdown"],
+        filenames: [],
+        interpreters: [],
+        injections: markdown::INJECTION_QUERY,
+    ),
+    language!(yaml, "yaml",
+        aliases: ["yml"],
+        extensions: ["yaml", "yml"],
+        filenames: [".clang-format", ".clang-tidy", ".clangd"],
+        interpreters: [],
+    ),
+    language!(xml, "xml",
+        aliases: ["svg"],
+        extensions: ["xml", "svg", "xsl", "xslt"],
+        filenames: [],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-hcl")]
+    language!(hcl, "hcl",
+        aliases: ["terraform", "tf", "opentofu"],
+        extensions: ["hcl", "tf", "tfvars", "tofu", "nomad"],
+        filenames: [".terraformrc", "terraform.rc"],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-nix")]
+    language!(nix, "nix",
+        aliases: [],
+        extensions: ["nix"],
+        filenames: [],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-protobuf")]
+    language!(protobuf, "protobuf",
+        aliases: ["proto", "proto3", "protocol buffer"],
+        extensions: ["proto"],
+        filenames: [],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-graphql")]
+    language!(graphql, "graphql",
+        aliases: ["gql"],
+        extensions: ["graphql", "gql", "graphqls"],
+        filenames: [],
+        interpreters: [],
+    ),
+    #[cfg(feature = "lang-vue")]
+    language!(vue, "vue",
+        aliases: [],
+        extensions: ["vue"],
+        filenames: [],
+        interpreters: [],
+        injections: vue::INJECTION_QUERY,
+    ),
+    #[cfg(feature = "lang-svelte")]
+    language!(svelte, "svelte",
+        aliases: [],
+        extensions: ["svelte"],
+        filenames: [],
+        interpreters: [],
+        injections: svelte::INJECTION_QUERY,
+    ),
+];
+
+/// Look up a language by its name or an alias, ignoring case, spaces, `-` and `_`
+/// (`Shell`, `shell-script`, `C++`, `JSON with Comments`)
+pub fn by_name(name: &str) -> Option<&'static LanguageInfo> {
+    let key = |name: &str| -> String {
+        name.chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '_'))
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = key(name);
+    LANGUAGES.iter().find(|info| {
+        key(info.name) == wanted || info.aliases.iter().any(|alias| key(alias) == wanted)
+    })
+}
+
+pub fn by_filename(file_name: &str) -> Option<&'static LanguageInfo> {
+    LANGUAGES
+        .iter()
+        .find(|info| info.matches_filename(file_name))
+}
+
+pub fn by_extension(extension: &str) -> Option<&'static LanguageInfo> {
+    LANGUAGES
+        .iter()
+        .find(|info| info.extensions.contains(&extension))
+}
+
+/// Language and highlight query for a path, by its file name or extension
+///
+/// Kept for library users; `syntax::detect_language` also reads modelines,
+/// shebangs and `.gitattributes`.
+#[allow(dead_code)]
 pub fn get_language(path: &Path) -> Option<(Language, &'static str)> {
-    let extension = path.extension()?.to_str()?;
-
-    match extension {
-        "rs" => Some((rust::language(), rust::HIGHLIGHT_QUERY)),
-        "ts" | "tsx" | "mts" | "cts" => Some((typescript::language(), typescript::HIGHLIGHT_QUERY)),
-        "js" | "jsx" | "mjs" | "cjs" => Some((javascript::language(), javascript::HIGHLIGHT_QUERY)),
-        "py" | "pyw" => Some((python::language(), python::HIGHLIGHT_QUERY)),
-        "sh" | "bash" | "zsh" => Some((bash::language(), bash::HIGHLIGHT_QUERY)),
-        "go" => Some((go_lang::language(), go_lang::HIGHLIGHT_QUERY)),
-        "rb" | "rbw" | "rake" | "gemspec" => Some((ruby::language(), ruby::HIGHLIGHT_QUERY)),
-        "swift" => Some((swift::language(), swift::HIGHLIGHT_QUERY)),
-        "kt" | "kts" => Some((kotlin::language(), kotlin::HIGHLIGHT_QUERY)),
-        "java" => Some((java::language(), java::HIGHLIGHT_QUERY)),
-        "php" | "php3" | "php4" | "php5" | "phtml" => Some((php::language(), php::HIGHLIGHT_QUERY)),
-        "cs" | "csx" => Some((csharp::language(), csharp::HIGHLIGHT_QUERY)),
-        // C++ before C to handle .h files (can be either)
-        "cpp" | "cc" | "cxx" | "c++" | "C" | "CPP" | "hpp" | "hh" | "hxx" | "h++" | "H" | "HPP"
-        | "tcc" | "inl" => Some((cpp::language(), cpp::HIGHLIGHT_QUERY)),
-        "c" | "h" => Some((c::language(), c::HIGHLIGHT_QUERY)),
-        "hs" | "lhs" => Some((haskell::language(), haskell::HIGHLIGHT_QUERY)),
-        "dart" => Some((dart::language(), dart::HIGHLIGHT_QUERY)),
-        "scala" | "sc" | "sbt" => Some((scala::language(), scala::HIGHLIGHT_QUERY)),
-        "clj" | "cljs" | "cljc" | "edn" => Some((clojure::language(), clojure::HIGHLIGHT_QUERY)),
-        "zig" => Some((zig::language(), zig::HIGHLIGHT_QUERY)),
-        "ex" | "exs" => Some((elixir::language(), elixir::HIGHLIGHT_QUERY)),
-        "erl" | "hrl" | "es" | "escript" => Some((erlang::language(), erlang::HIGHLIGHT_QUERY)),
-        "html" | "htm" => Some((html::language(), html::HIGHLIGHT_QUERY)),
-        "css" | "scss" | "sass" => Some((css::language(), css::HIGHLIGHT_QUERY)),
-        "json" | "jsonc" => Some((json::language(), json::HIGHLIGHT_QUERY)),
-        "md" | "markdown" => Some((markdown::language(), markdown::HIGHLIGHT_QUERY)),
-        "yaml" | "yml" => Some((yaml::language(), yaml::HIGHLIGHT_QUERY)),
-        "xml" | "svg" | "xsl" | "xslt" => Some((xml::language(), xml::HIGHLIGHT_QUERY)),
-        _ => None,
+    let info = by_filename(path.file_name()?.to_str()?)
+        .or_else(|| by_extension(path.extension()?.to_str()?))?;
+    Some((info.language(), info.highlight_query))
+}
+
+pub fn by_interpreter(interpreter: &str) -> Option<&'static LanguageInfo> {
+    LANGUAGES
+        .iter()
+        .find(|info| info.interpreters.contains(&interpreter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queries_compile() {
+        for info in LANGUAGES {
+            if let Err(error) = tree_sitter::Query::new(&info.language(), info.highlight_query) {
+                panic!("Invalid highlight query for {}: {}", info.name, error);
+            }
+            if info.injection_query.is_empty() {
+                continue;
+            }
+            if let Err(error) = tree_sitter::Query::new(&info.language(), info.injection_query) {
+                panic!("Invalid injection query for {}: {}", info.name, error);
+            }
+        }
+    }
+
+    #[test]
+    fn test_get_language() {
+        let (_, query) = get_language(Path::new("src/main.rs")).unwrap();
+        assert_eq!(query, rust::HIGHLIGHT_QUERY);
+        assert!(get_language(Path::new("notes.unknown")).is_none());
+    }
+
+    #[test]
+    fn test_optional_languages() {
+        let name = |path: &str| {
+            let path = Path::new(path);
+            by_filename(path.file_name()?.to_str()?)
+                .or_else(|| by_extension(path.extension()?.to_str()?))
+                .map(|info| info.name)
+        };
+        let expected =
+            |language: &'static str, enabled: bool| Some(if enabled { language } else { "bash" });
+        assert_eq!(
+            name("Dockerfile.prod"),
+            expected("dockerfile", cfg!(feature = "lang-dockerfile"))
+        );
+        assert_eq!(
+            name("GNUmakefile"),
+            expected("make", cfg!(feature = "lang-make"))
+        );
+        assert_eq!(
+            name("build/rules.mk"),
+            expected("make", cfg!(feature = "lang-make"))
+        );
+
+        #[cfg(feature = "lang-hcl")]
+        assert_eq!(by_name("Terraform").map(|info| info.name), Some("hcl"));
+        #[cfg(feature = "lang-toml")]
+        assert_eq!(name("Cargo.lock"), Some("toml"));
+        #[cfg(not(feature = "lang-vue"))]
+        assert_eq!(name("App.vue"), None);
     }
 }
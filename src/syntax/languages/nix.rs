@@ -0,0 +1,133 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_nix::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on tree_sitter_nix::HIGHLIGHTS_QUERY, with strings split into fragments
+// so that `${...}` interpolations keep their own colors
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+
+[
+  "if"
+  "then"
+  "else"
+  "let"
+  "inherit"
+  "in"
+  "rec"
+  "with"
+  "assert"
+  "or"
+] @keyword
+
+; Builtins
+((identifier) @constant.builtin
+  (#match? @constant.builtin "^(true|false|null)$"))
+
+((identifier) @variable.builtin
+  (#match? @variable.builtin "^(builtins|__currentSystem|__currentTime|__nixPath|__nixVersion|__storeDir)$"))
+
+((identifier) @function.builtin
+  (#match? @function.builtin "^(abort|baseNameOf|derivation|dirOf|fetchGit|fetchTarball|fetchTree|fetchurl|fromTOML|import|isNull|map|placeholder|removeAttrs|scopedImport|throw|toString)$"))
+
+; Functions
+(function_expression
+  universal: (identifier) @parameter)
+
+(formal
+  name: (identifier) @parameter)
+
+(apply_expression
+  function: (variable_expression
+    (identifier) @function))
+
+(apply_expression
+  function: (select_expression
+    attrpath: (attrpath
+      attr: (identifier) @function .)))
+
+; Attributes
+(binding
+  attrpath: (attrpath
+    (identifier) @property))
+
+(select_expression
+  attrpath: (attrpath
+    (identifier) @property))
+
+(has_attr_expression
+  (attrpath
+    (identifier) @property))
+
+(inherited_attrs
+  attr: (identifier) @property)
+
+(variable_expression
+  (identifier) @variable)
+
+(identifier) @variable
+
+; Literals
+[
+  (integer_expression)
+  (float_expression)
+] @number
+
+[
+  (escape_sequence)
+  (dollar_escape)
+] @string.escape
+
+(string_expression
+  "\"" @string)
+
+(indented_string_expression
+  "''" @string)
+
+(string_fragment) @string
+
+[
+  (path_expression)
+  (hpath_expression)
+  (spath_expression)
+  (uri_expression)
+] @string.special
+
+(interpolation
+  [
+    "${"
+    "}"
+  ] @punctuation.special)
+
+; Operators
+(unary_expression
+  operator: _ @operator)
+
+(binary_expression
+  operator: _ @operator)
+
+[
+  "="
+  "?"
+  "@"
+  ":"
+  (ellipses)
+] @operator
+
+; Punctuation
+[
+  ";"
+  "."
+  ","
+] @punctuation.delimiter
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+] @punctuation.bracket
+"#;
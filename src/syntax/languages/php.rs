@@ -3,3 +3,28 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_php::HIGHLIGHTS_QUERY;
+
+// HTML outside of `<?php ?>` tags, heredocs named after a language (`<<<SQL`) and SQL strings
+pub const INJECTION_QUERY: &str = concat!(
+    r#"
+((text) @injection.content
+  (#set! injection.language "html")
+  (#set! injection.combined))
+
+(heredoc
+  (heredoc_body) @injection.content
+  (heredoc_end) @injection.language
+  (#set! injection.include-children))
+
+(nowdoc
+  (nowdoc_body) @injection.content
+  (heredoc_end) @injection.language
+  (#set! injection.include-children))
+
+((string_content) @injection.content
+  "#,
+    sql_string_predicate!(),
+    r#"
+  (#set! injection.language "sql"))
+"#
+);
@@ -0,0 +1,151 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_proto::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on the grammar's queries/highlights.scm (not exported by the crate), with
+// field numbers and option names given their own colors
+pub const HIGHLIGHT_QUERY: &str = r#"
+(comment) @comment
+
+[
+  "syntax"
+  "edition"
+  "package"
+  "import"
+  "option"
+  "reserved"
+  "to"
+  "max"
+  "enum"
+  "extend"
+  "extensions"
+  "group"
+  "message"
+  "map"
+  "oneof"
+  "service"
+  "rpc"
+  "returns"
+  "export"
+  "local"
+  "optional"
+  "repeated"
+  "required"
+  "stream"
+  "weak"
+  "public"
+] @keyword
+
+; Types
+[
+  (key_type)
+  (type)
+] @type.builtin
+
+[
+  (message_name)
+  (enum_name)
+  (service_name)
+  (message_or_enum_type)
+] @type
+
+(package
+  (full_ident
+    (identifier) @namespace))
+
+(extend
+  (full_ident
+    (identifier) @type))
+
+(oneof
+  (identifier) @type)
+
+(rpc_name) @function.method
+
+; Fields and options
+(field
+  (identifier) @property)
+
+(map_field
+  (identifier) @property)
+
+(oneof_field
+  (identifier) @property)
+
+(enum_field
+  (identifier) @constant)
+
+(field_number) @number
+
+(option
+  (full_ident
+    (identifier) @attribute))
+
+(field_option
+  (full_ident
+    (identifier) @attribute))
+
+(enum_value_option
+  (full_ident
+    (identifier) @attribute))
+
+(option
+  (identifier) @attribute)
+
+(field_option
+  (identifier) @attribute)
+
+(enum_value_option
+  (identifier) @attribute)
+
+(block_lit
+  (identifier) @property)
+
+(constant
+  (full_ident
+    (identifier) @constant))
+
+; Literals
+(escape_sequence) @string.escape
+
+[
+  (string)
+  (reserved_identifier)
+] @string
+
+[
+  (int_lit)
+  (float_lit)
+] @number
+
+[
+  (true)
+  (false)
+] @boolean
+
+; Operators and punctuation
+[
+  "="
+  "-"
+  "+"
+] @operator
+
+[
+  "("
+  ")"
+  "["
+  "]"
+  "{"
+  "}"
+  "<"
+  ">"
+] @punctuation.bracket
+
+[
+  ";"
+  ","
+  "."
+  ":"
+] @punctuation.delimiter
+"#;
@@ -3,3 +3,19 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_ruby::HIGHLIGHTS_QUERY;
+
+// Heredocs named after a language (`<<~SQL`) and SQL strings
+pub const INJECTION_QUERY: &str = concat!(
+    r#"
+(heredoc_body
+  (heredoc_content) @injection.content
+  (heredoc_end) @injection.language)
+
+(string
+  (string_content) @injection.content
+  "#,
+    sql_string_predicate!(),
+    r#"
+  (#set! injection.language "sql"))
+"#
+);
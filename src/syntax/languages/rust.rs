@@ -3,3 +3,14 @@ pub fn language() -> tree_sitter::Language {
 }
 
 pub const HIGHLIGHT_QUERY: &str = tree_sitter_rust::HIGHLIGHTS_QUERY;
+
+// SQL in string literals
+pub const INJECTION_QUERY: &str = concat!(
+    r#"
+((string_content) @injection.content
+  "#,
+    sql_string_predicate!(),
+    r#"
+  (#set! injection.language "sql"))
+"#
+);
@@ -0,0 +1,98 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_svelte_ng::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on tree_sitter_svelte_ng::HIGHLIGHTS_QUERY with the HTML rules it inherits
+// folded in, so that `{#if}` blocks and tags are colored without nvim's query inheritance
+pub const HIGHLIGHT_QUERY: &str = r##"
+(comment) @comment
+
+; Tags
+(tag_name) @tag
+(erroneous_end_tag_name) @tag
+(doctype) @keyword
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"
+  "<!"
+] @punctuation.delimiter
+
+; Blocks and special tags
+[
+  "if"
+  "else"
+  "else if"
+  "then"
+  "each"
+  "as"
+  "await"
+  "catch"
+  "key"
+  "html"
+  "const"
+  "debug"
+  "snippet"
+  "render"
+] @keyword
+
+(snippet_name) @function
+
+[
+  "#"
+  ":"
+  "/"
+  "@"
+] @punctuation.special
+
+[
+  "{"
+  "}"
+  "("
+  ")"
+] @punctuation.bracket
+
+; Attributes
+(attribute_name) @property
+"=" @operator
+
+(quoted_attribute_value) @string
+(attribute_value) @string
+
+(entity) @string.escape
+"##;
+
+// `<script lang="ts">` and `<style>` blocks and `{...}` expressions
+pub const INJECTION_QUERY: &str = r#"
+((script_element
+  (start_tag
+    (attribute
+      (attribute_name) @_attr
+      (quoted_attribute_value
+        (attribute_value) @injection.language)))
+  (raw_text) @injection.content)
+  (#eq? @_attr "lang"))
+
+((script_element
+  (raw_text) @injection.content)
+  (#set! injection.language "javascript"))
+
+((style_element
+  (start_tag
+    (attribute
+      (attribute_name) @_attr
+      (quoted_attribute_value
+        (attribute_value) @injection.language)))
+  (raw_text) @injection.content)
+  (#eq? @_attr "lang"))
+
+((style_element
+  (raw_text) @injection.content)
+  (#set! injection.language "css"))
+
+((svelte_raw_text) @injection.content
+  (#set! injection.language "javascript"))
+"#;
@@ -0,0 +1,54 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_toml_ng::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on tree_sitter_toml_ng::HIGHLIGHTS_QUERY, with table headers set apart from keys
+// and without capturing whole pairs
+pub const HIGHLIGHT_QUERY: &str = r#"
+; Table headers
+(table (bare_key) @type)
+(table (dotted_key (bare_key) @type))
+(table (quoted_key) @type)
+(table_array_element (bare_key) @type)
+(table_array_element (dotted_key (bare_key) @type))
+(table_array_element (quoted_key) @type)
+
+; Keys
+(bare_key) @property
+(quoted_key) @property
+
+; Literals
+(comment) @comment
+(string) @string
+(boolean) @boolean
+
+[
+  (integer)
+  (float)
+] @number
+
+[
+  (offset_date_time)
+  (local_date_time)
+  (local_date)
+  (local_time)
+] @constant
+
+; Punctuation
+"=" @operator
+
+[
+  "."
+  ","
+] @punctuation.delimiter
+
+[
+  "["
+  "]"
+  "[["
+  "]]"
+  "{"
+  "}"
+] @punctuation.bracket
+"#;
@@ -0,0 +1,108 @@
+pub fn language() -> tree_sitter::Language {
+    tree_sitter_vue_next::LANGUAGE.into()
+}
+
+// Custom highlight query for better visibility
+// Based on the grammar's html_tags and vue queries, with directives (`v-if`, `:prop`,
+// `@click`) set apart from plain attributes
+pub const HIGHLIGHT_QUERY: &str = r##"
+(comment) @comment
+
+; Tags
+(tag_name) @tag
+(erroneous_end_tag_name) @tag
+(doctype) @keyword
+
+[
+  "<"
+  ">"
+  "</"
+  "/>"
+  "<!"
+] @punctuation.delimiter
+
+; Directives
+(directive_name) @keyword
+
+(directive_attribute
+  ":" @operator
+  .
+  (directive_value) @property)
+
+(directive_attribute
+  "@" @operator
+  .
+  (directive_value) @function)
+
+(directive_attribute
+  "#" @operator
+  .
+  (directive_value) @variable)
+
+(dynamic_directive_value
+  [
+    "["
+    "]"
+  ] @punctuation.bracket)
+
+(dynamic_directive_inner_value) @variable
+
+(directive_modifiers
+  "." @punctuation.delimiter)
+
+(directive_modifier) @function.method
+
+; Attributes
+(attribute_name) @property
+"=" @operator
+
+(quoted_attribute_value) @string
+(attribute_value) @string
+
+; Template interpolation
+(interpolation
+  [
+    "{{"
+    "}}"
+  ] @punctuation.special)
+
+(entity) @string.escape
+"##;
+
+// `<script lang="ts">` and `<style>` blocks, `{{ }}` interpolations and directive values
+pub const INJECTION_QUERY: &str = r#"
+((script_element
+  (start_tag
+    (attribute
+      (attribute_name) @_attr
+      (quoted_attribute_value
+        (attribute_value) @injection.language)))
+  (raw_text) @injection.content)
+  (#eq? @_attr "lang"))
+
+((script_element
+  (raw_text) @injection.content)
+  (#set! injection.language "javascript"))
+
+((style_element
+  (start_tag
+    (attribute
+      (attribute_name) @_attr
+      (quoted_attribute_value
+        (attribute_value) @injection.language)))
+  (raw_text) @injection.content)
+  (#eq? @_attr "lang"))
+
+((style_element
+  (raw_text) @injection.content)
+  (#set! injection.language "css"))
+
+((interpolation
+  (raw_text) @injection.content)
+  (#set! injection.language "javascript"))
+
+((directive_attribute
+  (quoted_attribute_value
+    (attribute_value) @injection.content))
+  (#set! injection.language "javascript"))
+"#;
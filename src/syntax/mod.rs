@@ -1,12 +1,16 @@
+mod detect;
 pub mod languages;
 
 use crate::theme::Theme;
 use ratatui::style::Color;
+use std::collections::HashMap;
 use std::path::Path;
+use std::rc::Rc;
 use streaming_iterator::StreamingIterator;
-use tree_sitter::{Language, Parser, Query, QueryCursor};
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor, Range, Tree};
 
-pub use languages::get_language;
+pub use detect::{detect_language, init_language_mappings};
+use languages::LanguageInfo;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TokenType {
@@ -52,34 +56,56 @@ pub struct HighlightSpan {
     pub token_type: TokenType,
 }
 
+// Injections nested deeper than this keep their host's highlighting
+const MAX_INJECTION_DEPTH: usize = 3;
+
+/// A language's grammar with its compiled highlight and injection queries
+struct CompiledLanguage {
+    language: Language,
+    highlights: Query,
+    injections: Option<Query>,
+}
+
+impl CompiledLanguage {
+    fn new(info: &LanguageInfo) -> Option<Self> {
+        let language = info.language();
+        let highlights = Query::new(&language, info.highlight_query).ok()?;
+        let injections = Some(info.injection_query)
+            .filter(|source| !source.is_empty())
+            .and_then(|source| Query::new(&language, source).ok());
+        Some(Self {
+            language,
+            highlights,
+            injections,
+        })
+    }
+}
+
+/// Source ranges to highlight with another language's grammar
+struct Injection {
+    info: &'static LanguageInfo,
+    ranges: Vec<Range>,
+    pattern_index: usize,
+}
+
 pub struct Highlighter {
     parser: Parser,
-    language: Option<Language>,
-    query: Option<Query>,
-    query_source: Option<String>,
+    info: Option<&'static LanguageInfo>,
+    /// Compiled queries by language name, including injected languages (`None` if invalid)
+    compiled: HashMap<&'static str, Option<Rc<CompiledLanguage>>>,
     cached_tree: Option<tree_sitter::Tree>,
     cached_source: String,
 }
 
 impl Clone for Highlighter {
     fn clone(&self) -> Self {
-        let mut new_parser = Parser::new();
-        let query = if let (Some(ref lang), Some(ref source)) = (&self.language, &self.query_source)
-        {
-            let _ = new_parser.set_language(lang);
-            Query::new(lang, source).ok()
-        } else {
-            None
-        };
-
-        Self {
-            parser: new_parser,
-            language: self.language.clone(),
-            query,
-            query_source: self.query_source.clone(),
-            cached_tree: None,
-            cached_source: String::new(),
+        let mut highlighter = Self::new();
+        if let Some(info) = self.info {
+            if highlighter.set_language(info) {
+                highlighter.info = Some(info);
+            }
         }
+        highlighter
     }
 }
 
@@ -87,41 +113,48 @@ impl Highlighter {
     pub fn new() -> Self {
         Self {
             parser: Parser::new(),
-            language: None,
-            query: None,
-            query_source: None,
+            info: None,
+            compiled: HashMap::new(),
             cached_tree: None,
             cached_source: String::new(),
         }
     }
 
+    #[allow(dead_code)]
     pub fn set_language_from_path(&mut self, path: &str) -> bool {
-        if let Some((language, query_source)) = get_language(Path::new(path)) {
-            if self.parser.set_language(&language).is_ok() {
-                if let Ok(query) = Query::new(&language, query_source) {
-                    self.language = Some(language);
-                    self.query = Some(query);
-                    self.query_source = Some(query_source.to_string());
-                    self.cached_tree = None;
-                    self.cached_source = String::new();
-                    return true;
-                }
-            }
-        }
-        // Language not supported - clear previous language settings
-        self.language = None;
-        self.query = None;
-        self.query_source = None;
+        self.set_language_for_file(path, "", None)
+    }
+
+    /// Pick the language from the file name, its content (shebang, modeline)
+    /// and an optional `linguist-language` attribute
+    pub fn set_language_for_file(
+        &mut self,
+        path: &str,
+        content: &str,
+        linguist_language: Option<&str>,
+    ) -> bool {
+        let info = detect_language(Path::new(path), content, linguist_language);
+        self.info = info.filter(|info| self.set_language(info));
         self.cached_tree = None;
         self.cached_source = String::new();
-        false
+        self.info.is_some()
     }
 
-    pub fn highlight(&mut self, source: &str) -> Vec<HighlightSpan> {
-        let mut spans = Vec::new();
+    fn set_language(&mut self, info: &'static LanguageInfo) -> bool {
+        self.compiled_language(info)
+            .is_some_and(|compiled| self.parser.set_language(&compiled.language).is_ok())
+    }
 
-        let Some(query) = &self.query else {
-            return spans;
+    fn compiled_language(&mut self, info: &'static LanguageInfo) -> Option<Rc<CompiledLanguage>> {
+        self.compiled
+            .entry(info.name)
+            .or_insert_with(|| CompiledLanguage::new(info).map(Rc::new))
+            .clone()
+    }
+
+    pub fn highlight(&mut self, source: &str) -> Vec<HighlightSpan> {
+        let Some(compiled) = self.info.and_then(|info| self.compiled_language(info)) else {
+            return Vec::new();
         };
 
         // Use incremental parsing only if source hasn't changed
@@ -132,79 +165,245 @@ impl Highlighter {
         };
 
         let Some(tree) = self.parser.parse(source, old_tree) else {
-            return spans;
+            return Vec::new();
         };
 
-        // Cache the tree and source for next incremental parse (clone needed because matches borrows tree)
+        // Cache the tree and source for next incremental parse
         self.cached_tree = Some(tree.clone());
         self.cached_source = source.to_string();
 
-        let mut cursor = QueryCursor::new();
-        let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
-
-        while let Some(query_match) = matches.next() {
-            for capture in query_match.captures {
-                let node = capture.node;
-                let capture_name = &query.capture_names()[capture.index as usize];
-
-                // Handle dotted capture names like "keyword.function" -> "keyword"
-                let base_name = capture_name.split('.').next().unwrap_or(capture_name);
-
-                let token_type = match base_name {
-                    "keyword" => TokenType::Keyword,
-                    "type" => TokenType::Type,
-                    "function" => TokenType::Function,
-                    "variable" => TokenType::Variable,
-                    "string" => TokenType::String,
-                    "number" => TokenType::Number,
-                    "comment" => TokenType::Comment,
-                    "operator" => TokenType::Operator,
-                    "punctuation" => TokenType::Punctuation,
-                    "constant" => TokenType::Constant,
-                    "parameter" => TokenType::Parameter,
-                    "property" => TokenType::Property,
-                    "label" => TokenType::Label,
-                    "character" => TokenType::String,
-                    "boolean" => TokenType::Constant,
-                    // Additional common capture names
-                    "namespace" | "module" => TokenType::Type,
-                    "constructor" => TokenType::Type,
-                    "method" => TokenType::Function,
-                    "macro" => TokenType::Function,
-                    "annotation" | "attribute" | "decorator" => TokenType::Keyword,
-                    "tag" => TokenType::Type,
-                    "escape" => TokenType::Operator,
-                    "delimiter" => TokenType::Punctuation,
-                    "special" => TokenType::Operator,
-                    "field" => TokenType::Property,
-                    "enum" | "struct" | "class" | "interface" | "trait" => TokenType::Type,
-                    "regexp" => TokenType::String,
-                    // Additional from all language queries
-                    "conditional" | "repeat" | "exception" | "include" | "storageclass" => {
-                        TokenType::Keyword
-                    }
-                    "identifier" => TokenType::Variable,
-                    "float" => TokenType::Number,
-                    // Markdown and documentation
-                    "text" => TokenType::String,
-                    // Skip internal/special markers
-                    "embedded" | "spell" | "__name__" | "_name" | "_op" | "_type" | "none" => {
-                        continue
-                    }
-                    _ => continue,
-                };
-
-                spans.push(HighlightSpan {
-                    start: node.start_byte(),
-                    end: node.end_byte(),
-                    token_type,
+        let mut spans = self.highlight_tree(&compiled, &tree, source, 0);
+        spans.sort_by_key(|span| span.start);
+        spans
+    }
+
+    /// Highlight a tree, then re-highlight its injections (fenced code blocks,
+    /// `<script>` elements, SQL strings, ...) with their own grammars
+    fn highlight_tree(
+        &mut self,
+        compiled: &CompiledLanguage,
+        tree: &Tree,
+        source: &str,
+        depth: usize,
+    ) -> Vec<HighlightSpan> {
+        let mut spans = capture_spans(&compiled.highlights, tree, source);
+        let Some(injections) = compiled.injections.as_ref() else {
+            return spans;
+        };
+        if depth >= MAX_INJECTION_DEPTH {
+            return spans;
+        }
+
+        for injection in find_injections(injections, tree, source) {
+            let Some(injected) = self.compiled_language(injection.info) else {
+                continue;
+            };
+            let mut parser = Parser::new();
+            if parser.set_language(&injected.language).is_err()
+                || parser.set_included_ranges(&injection.ranges).is_err()
+            {
+                continue;
+            }
+            let Some(injected_tree) = parser.parse(source, None) else {
+                continue;
+            };
+
+            // The host's spans give way to the injected language inside its ranges
+            spans = spans
+                .into_iter()
+                .flat_map(|span| span.split_around(&injection.ranges))
+                .collect();
+            spans.extend(self.highlight_tree(&injected, &injected_tree, source, depth + 1));
+        }
+        spans
+    }
+}
+
+impl HighlightSpan {
+    /// The parts of this span outside the given sorted, non-overlapping ranges
+    fn split_around(self, ranges: &[Range]) -> Vec<HighlightSpan> {
+        let mut parts = Vec::new();
+        let mut start = self.start;
+        for range in ranges {
+            if range.end_byte <= start || range.start_byte >= self.end {
+                continue;
+            }
+            if range.start_byte > start {
+                parts.push(HighlightSpan {
+                    start,
+                    end: range.start_byte,
+                    token_type: self.token_type,
                 });
             }
+            start = start.max(range.end_byte);
+        }
+        if start < self.end {
+            parts.push(HighlightSpan { start, ..self });
         }
+        parts
+    }
+}
 
-        spans.sort_by_key(|span| span.start);
-        spans
+fn capture_spans(query: &Query, tree: &Tree, source: &str) -> Vec<HighlightSpan> {
+    let mut spans = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+
+    while let Some(query_match) = matches.next() {
+        for capture in query_match.captures {
+            let node = capture.node;
+            let capture_name = &query.capture_names()[capture.index as usize];
+
+            // Handle dotted capture names like "keyword.function" -> "keyword"
+            let base_name = capture_name.split('.').next().unwrap_or(capture_name);
+
+            let token_type = match base_name {
+                "keyword" => TokenType::Keyword,
+                "type" => TokenType::Type,
+                "function" => TokenType::Function,
+                "variable" => TokenType::Variable,
+                "string" => TokenType::String,
+                "number" => TokenType::Number,
+                "comment" => TokenType::Comment,
+                "operator" => TokenType::Operator,
+                "punctuation" => TokenType::Punctuation,
+                "constant" => TokenType::Constant,
+                "parameter" => TokenType::Parameter,
+                "property" => TokenType::Property,
+                "label" => TokenType::Label,
+                "character" => TokenType::String,
+                "boolean" => TokenType::Constant,
+                // Additional common capture names
+                "namespace" | "module" => TokenType::Type,
+                "constructor" => TokenType::Type,
+                "method" => TokenType::Function,
+                "macro" => TokenType::Function,
+                "annotation" | "attribute" | "decorator" => TokenType::Keyword,
+                "tag" => TokenType::Type,
+                "escape" => TokenType::Operator,
+                "delimiter" => TokenType::Punctuation,
+                "special" => TokenType::Operator,
+                "field" => TokenType::Property,
+                "enum" | "struct" | "class" | "interface" | "trait" => TokenType::Type,
+                "regexp" => TokenType::String,
+                // Additional from all language queries
+                "conditional" | "repeat" | "exception" | "include" | "storageclass" => {
+                    TokenType::Keyword
+                }
+                "identifier" => TokenType::Variable,
+                "float" => TokenType::Number,
+                // Markdown and documentation
+                "text" => TokenType::String,
+                // Skip internal/special markers
+                "embedded" | "spell" | "__name__" | "_name" | "_op" | "_type" | "none" => continue,
+                _ => continue,
+            };
+            spans.push(HighlightSpan {
+                start: node.start_byte(),
+                end: node.end_byte(),
+                token_type,
+            });
+        }
+    }
+    spans
+}
+
+/// Collect the `@injection.content` ranges of an injection query with their
+/// languages, named by an `@injection.language` capture or by
+/// `#set! injection.language`. Matches of an `injection.combined` pattern are
+/// parsed as one document; when two patterns inject into the same ranges, the
+/// earlier one wins.
+fn find_injections(query: &Query, tree: &Tree, source: &str) -> Vec<Injection> {
+    let Some(content_index) = query.capture_index_for_name("injection.content") else {
+        return Vec::new();
+    };
+    let language_index = query.capture_index_for_name("injection.language");
+
+    let mut injections: Vec<Injection> = Vec::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(query, tree.root_node(), source.as_bytes());
+    while let Some(query_match) = matches.next() {
+        let properties = query.property_settings(query_match.pattern_index);
+        let property = |key: &str| properties.iter().find(|property| &*property.key == key);
+
+        let name = query_match
+            .captures
+            .iter()
+            .find(|capture| Some(capture.index) == language_index)
+            .and_then(|capture| capture.node.utf8_text(source.as_bytes()).ok())
+            .or_else(|| property("injection.language")?.value.as_deref());
+        let Some(info) = name.and_then(languages::by_name) else {
+            continue;
+        };
+        let include_children = property("injection.include-children").is_some();
+        let ranges = query_match
+            .captures
+            .iter()
+            .filter(|capture| capture.index == content_index)
+            .flat_map(|capture| content_ranges(capture.node, include_children));
+
+        let combined = property("injection.combined").is_some();
+        let existing = injections.iter_mut().find(|injection| {
+            combined
+                && injection.pattern_index == query_match.pattern_index
+                && injection.info.name == info.name
+        });
+        match existing {
+            Some(injection) => injection.ranges.extend(ranges),
+            None => injections.push(Injection {
+                info,
+                ranges: ranges.collect(),
+                pattern_index: query_match.pattern_index,
+            }),
+        }
+    }
+
+    for injection in &mut injections {
+        injection.ranges.sort_by_key(|range| range.start_byte);
+        injection
+            .ranges
+            .dedup_by(|range, previous| range.start_byte < previous.end_byte);
+    }
+    injections.retain(|injection| !injection.ranges.is_empty());
+    injections.sort_by_key(|injection| injection.pattern_index);
+    let mut unique: Vec<Injection> = Vec::new();
+    for injection in injections {
+        if !unique.iter().any(|other| other.ranges == injection.ranges) {
+            unique.push(injection);
+        }
+    }
+    unique
+}
+
+/// The range of an injected node, without the ranges of its named children
+/// unless `injection.include-children` is set
+fn content_ranges(node: Node, include_children: bool) -> Vec<Range> {
+    if include_children {
+        return vec![node.range()];
+    }
+    let mut ranges = Vec::new();
+    let mut start = (node.start_byte(), node.start_position());
+    let mut cursor = node.walk();
+    for child in node.named_children(&mut cursor) {
+        if child.start_byte() > start.0 {
+            ranges.push(Range {
+                start_byte: start.0,
+                start_point: start.1,
+                end_byte: child.start_byte(),
+                end_point: child.start_position(),
+            });
+        }
+        start = (child.end_byte(), child.end_position());
     }
+    if node.end_byte() > start.0 {
+        ranges.push(Range {
+            start_byte: start.0,
+            start_point: start.1,
+            end_byte: node.end_byte(),
+            end_point: node.end_position(),
+        });
+    }
+    ranges
 }
 
 impl Default for Highlighter {
@@ -212,3 +411,96 @@ impl Default for Highlighter {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The token of the first character of `text`, picked the way the editor does
+    fn token_at(spans: &[HighlightSpan], source: &str, text: &str) -> Option<TokenType> {
+        let start = source.find(text).unwrap();
+        spans
+            .iter()
+            .find(|span| start >= span.start && start < span.end)
+            .map(|span| span.token_type)
+    }
+
+    fn highlight(path: &str, source: &str) -> Vec<HighlightSpan> {
+        let mut highlighter = Highlighter::new();
+        assert!(highlighter.set_language_from_path(path));
+        highlighter.highlight(source)
+    }
+
+    #[test]
+    fn test_markdown_code_blocks_use_their_language() {
+        let source = "# Title\n\n```rust\nfn main() {}\n```\n\n```\nfn plain() {}\n```\n";
+        let spans = highlight("README.md", source);
+        assert_eq!(
+            token_at(&spans, source, "fn main"),
+            Some(TokenType::Keyword)
+        );
+        assert_eq!(
+            token_at(&spans, source, "main()"),
+            Some(TokenType::Function)
+        );
+        // Without a language the block stays a single literal
+        assert_eq!(token_at(&spans, source, "plain"), Some(TokenType::String));
+        assert!(spans.windows(2).all(|pair| pair[0].start <= pair[1].start));
+    }
+
+    #[test]
+    fn test_html_and_php_injections() {
+        let source = "<script>const answer = 42;</script>\n";
+        let spans = highlight("index.html", source);
+        assert_eq!(token_at(&spans, source, "const"), Some(TokenType::Keyword));
+        assert_eq!(token_at(&spans, source, "42"), Some(TokenType::Number));
+
+        let source = "<div class=\"box\"><?php echo $name; ?></div>\n";
+        let spans = highlight("index.php", source);
+        assert_eq!(token_at(&spans, source, "div"), Some(TokenType::Type));
+        assert_eq!(token_at(&spans, source, "echo"), Some(TokenType::Keyword));
+    }
+
+    #[cfg(feature = "lang-sql")]
+    #[test]
+    fn test_sql_in_strings() {
+        let source = "query = \"SELECT name FROM users\"\nlabel = \"Selected: none\"\n";
+        let spans = highlight("app.py", source);
+        assert_eq!(token_at(&spans, source, "FROM"), Some(TokenType::Keyword));
+        assert_eq!(
+            token_at(&spans, source, "\"SELECT"),
+            Some(TokenType::String)
+        );
+        assert_eq!(
+            token_at(&spans, source, "Selected"),
+            Some(TokenType::String)
+        );
+
+        // Keywords are matched regardless of case
+        let source = "rows = db.execute(\"select name from users where id = 1\")\n";
+        let spans = highlight("app.rb", source);
+        assert_eq!(token_at(&spans, source, "from"), Some(TokenType::Keyword));
+        assert_eq!(token_at(&spans, source, "where"), Some(TokenType::Keyword));
+    }
+
+    #[test]
+    fn test_split_around() {
+        let span = HighlightSpan {
+            start: 0,
+            end: 10,
+            token_type: TokenType::String,
+        };
+        let range = |start_byte, end_byte| Range {
+            start_byte,
+            end_byte,
+            start_point: Default::default(),
+            end_point: Default::default(),
+        };
+        let parts: Vec<(usize, usize)> = span
+            .split_around(&[range(2, 4), range(6, 12)])
+            .iter()
+            .map(|part| (part.start, part.end))
+            .collect();
+        assert_eq!(parts, vec![(0, 2), (4, 6)]);
+    }
+}
@@ -0,0 +1,425 @@
+use super::Theme;
+use ratatui::style::Color;
+use std::sync::OnceLock;
+
+/// How many colors the terminal can show
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorDepth {
+    /// 24-bit RGB
+    #[value(name = "truecolor", alias = "24bit")]
+    TrueColor,
+    /// The xterm 256-color palette
+    #[value(name = "256")]
+    Ansi256,
+    /// The 16 ANSI colors
+    #[value(name = "16")]
+    Ansi16,
+    /// No colors at all
+    #[value(name = "mono", alias = "none")]
+    Mono,
+}
+
+impl ColorDepth {
+    /// Detect the color depth from `NO_COLOR`, `COLORTERM` and `TERM`
+    pub fn detect() -> Self {
+        Self::from_env(|name| std::env::var(name).ok())
+    }
+
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> Self {
+        let var = |name: &str| var(name).filter(|value| !value.is_empty());
+
+        // https://no-color.org
+        if var("NO_COLOR").is_some() {
+            return Self::Mono;
+        }
+
+        if let Some(colorterm) = var("COLORTERM") {
+            if colorterm == "truecolor" || colorterm == "24bit" {
+                return Self::TrueColor;
+            }
+        }
+
+        let Some(term) = var("TERM") else {
+            // Windows terminals do not set TERM
+            return if cfg!(windows) {
+                Self::TrueColor
+            } else {
+                Self::Ansi16
+            };
+        };
+
+        let truecolor_terminal = var("WT_SESSION").is_some()
+            || matches!(
+                var("TERM_PROGRAM").as_deref(),
+                Some("iTerm.app" | "WezTerm" | "vscode" | "ghostty")
+            );
+
+        if term == "dumb" {
+            Self::Mono
+        } else if term.ends_with("-direct")
+            || term.contains("truecolor")
+            || term.contains("24bit")
+            || truecolor_terminal
+        {
+            Self::TrueColor
+        } else if term.contains("256color") {
+            Self::Ansi256
+        } else {
+            // Plain xterm, screen and tmux, and the Linux console
+            Self::Ansi16
+        }
+    }
+
+    /// The closest color this depth can show
+    pub fn convert(self, color: Color) -> Color {
+        self.convert_avoiding(color, &[])
+    }
+
+    /// Like `convert`, but never picks one of `avoid` when quantizing,
+    /// so that text does not vanish into a background of the same color
+    fn convert_avoiding(self, color: Color, avoid: &[Color]) -> Color {
+        match (self, color) {
+            (_, Color::Reset) => Color::Reset,
+            (Self::TrueColor, color) => color,
+            (Self::Mono, _) => Color::Reset,
+            (Self::Ansi256, Color::Rgb(r, g, b)) => Color::Indexed(nearest(
+                Lab::from_rgb(r, g, b),
+                &palette_256()[16..],
+                avoid,
+                16,
+                Lab::distance,
+            )),
+            // Named colors and palette indices are already available
+            (Self::Ansi256, color) => color,
+            (Self::Ansi16, Color::Indexed(index)) if index >= 16 => {
+                let (r, g, b) = xterm_rgb(index);
+                self.convert_avoiding(Color::Rgb(r, g, b), avoid)
+            }
+            (Self::Ansi16, Color::Indexed(index)) => ANSI_16[index as usize],
+            (Self::Ansi16, Color::Rgb(r, g, b)) => {
+                let index = nearest(
+                    Lab::from_rgb(r, g, b),
+                    palette_16(),
+                    avoid,
+                    0,
+                    Lab::hue_distance,
+                );
+                ANSI_16[index as usize]
+            }
+            (Self::Ansi16, color) => color,
+        }
+    }
+}
+
+impl Theme {
+    /// Quantize every color to what a terminal of `depth` can show
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        if depth == ColorDepth::TrueColor {
+            return self;
+        }
+
+        let backgrounds = [
+            depth.convert(self.background_left),
+            depth.convert(self.background_right),
+        ];
+        for key in Theme::COLOR_KEYS {
+            let Some(slot) = self.color_mut(key) else {
+                continue;
+            };
+            // Backgrounds and the text drawn on highlights keep their nearest match;
+            // other text is kept distinguishable from the pane backgrounds
+            let avoid: &[Color] =
+                if key.starts_with("background_") || key.ends_with("_bg") || key.ends_with("_fg") {
+                    &[]
+                } else {
+                    &backgrounds
+                };
+            *slot = depth.convert_avoiding(*slot, avoid);
+        }
+        self
+    }
+}
+
+/// RGB a color is drawn with, or `None` for the terminal's default color
+pub fn color_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    match color {
+        Color::Reset => None,
+        Color::Rgb(r, g, b) => Some((r, g, b)),
+        Color::Indexed(index) => Some(xterm_rgb(index)),
+        named => ANSI_16
+            .iter()
+            .position(|ansi| *ansi == named)
+            .map(|index| xterm_rgb(index as u8)),
+    }
+}
+
+/// The 16 ANSI colors in palette order
+const ANSI_16: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// RGB of an xterm palette index; 0-15 use the VGA colors of the Linux console,
+/// as terminals each pick their own
+fn xterm_rgb(index: u8) -> (u8, u8, u8) {
+    const VGA: [(u8, u8, u8); 16] = [
+        (0x00, 0x00, 0x00),
+        (0xaa, 0x00, 0x00),
+        (0x00, 0xaa, 0x00),
+        (0xaa, 0x55, 0x00),
+        (0x00, 0x00, 0xaa),
+        (0xaa, 0x00, 0xaa),
+        (0x00, 0xaa, 0xaa),
+        (0xaa, 0xaa, 0xaa),
+        (0x55, 0x55, 0x55),
+        (0xff, 0x55, 0x55),
+        (0x55, 0xff, 0x55),
+        (0xff, 0xff, 0x55),
+        (0x55, 0x55, 0xff),
+        (0xff, 0x55, 0xff),
+        (0x55, 0xff, 0xff),
+        (0xff, 0xff, 0xff),
+    ];
+    const CUBE: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+    match index {
+        0..=15 => VGA[index as usize],
+        16..=231 => {
+            let index = index - 16;
+            (
+                CUBE[(index / 36) as usize],
+                CUBE[(index / 6 % 6) as usize],
+                CUBE[(index % 6) as usize],
+            )
+        }
+        _ => {
+            let level = 8 + (index - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+fn palette_256() -> &'static [Lab] {
+    static PALETTE: OnceLock<Vec<Lab>> = OnceLock::new();
+    PALETTE.get_or_init(|| {
+        (0..=255)
+            .map(|index| {
+                let (r, g, b) = xterm_rgb(index);
+                Lab::from_rgb(r, g, b)
+            })
+            .collect()
+    })
+}
+
+fn palette_16() -> &'static [Lab] {
+    &palette_256()[..16]
+}
+
+/// Index (offset by `first`) of the palette entry closest to `target`
+/// by `distance` that does not convert to one of `avoid`
+fn nearest(
+    target: Lab,
+    palette: &[Lab],
+    avoid: &[Color],
+    first: u8,
+    distance: fn(&Lab, &Lab) -> f64,
+) -> u8 {
+    let allowed = |index: u8| {
+        let color = if first == 0 {
+            ANSI_16[index as usize]
+        } else {
+            Color::Indexed(index)
+        };
+        !avoid.contains(&color)
+    };
+
+    palette
+        .iter()
+        .enumerate()
+        .map(|(offset, lab)| (first + offset as u8, distance(&target, lab)))
+        .filter(|&(index, _)| allowed(index))
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map_or(first, |(index, _)| index)
+}
+
+/// A color in CIELAB, where euclidean distance approximates perceived difference
+#[derive(Debug, Clone, Copy)]
+struct Lab {
+    l: f64,
+    a: f64,
+    b: f64,
+}
+
+impl Lab {
+    fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        let linear = |channel: u8| {
+            let c = channel as f64 / 255.0;
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (r, g, b) = (linear(r), linear(g), linear(b));
+
+        // sRGB -> XYZ (D65), normalized by the reference white
+        let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+        let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+        let f = |t: f64| {
+            if t > 0.008856 {
+                t.cbrt()
+            } else {
+                7.787 * t + 16.0 / 116.0
+            }
+        };
+        let (fx, fy, fz) = (f(x), f(y), f(z));
+        Self {
+            l: 116.0 * fy - 16.0,
+            a: 500.0 * (fx - fy),
+            b: 200.0 * (fy - fz),
+        }
+    }
+
+    fn distance(&self, other: &Lab) -> f64 {
+        ((self.l - other.l).powi(2) + (self.a - other.a).powi(2) + (self.b - other.b).powi(2))
+            .sqrt()
+    }
+
+    fn chroma(&self) -> f64 {
+        self.a.hypot(self.b)
+    }
+
+    /// Distance for sparse palettes such as the 16 ANSI colors, where the
+    /// nearest entry by plain distance is often a gray
+    ///
+    /// Muted colors only match grays and colorful ones only match colors,
+    /// and hue counts for more than lightness and saturation.
+    fn hue_distance(&self, other: &Lab) -> f64 {
+        const MUTED_CHROMA: f64 = 25.0;
+        if (self.chroma() < MUTED_CHROMA) != (other.chroma() < MUTED_CHROMA) {
+            return f64::INFINITY;
+        }
+
+        let (c1, c2) = (self.chroma(), other.chroma());
+        let hue_angle = self.b.atan2(self.a) - other.b.atan2(other.a);
+        let hue = 2.0 * (c1 * c2).sqrt() * (hue_angle / 2.0).sin();
+        ((0.5 * (self.l - other.l)).powi(2) + (0.5 * (c1 - c2)).powi(2) + (2.0 * hue).powi(2))
+            .sqrt()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect(vars: &[(&str, &str)]) -> ColorDepth {
+        ColorDepth::from_env(|name| {
+            vars.iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+        })
+    }
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(
+            detect(&[("COLORTERM", "truecolor"), ("TERM", "xterm-256color")]),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(detect(&[("TERM", "xterm-256color")]), ColorDepth::Ansi256);
+        assert_eq!(detect(&[("TERM", "tmux-256color")]), ColorDepth::Ansi256);
+        assert_eq!(detect(&[("TERM", "xterm-direct")]), ColorDepth::TrueColor);
+        assert_eq!(detect(&[("TERM", "linux")]), ColorDepth::Ansi16);
+        assert_eq!(detect(&[("TERM", "screen")]), ColorDepth::Ansi16);
+        assert_eq!(detect(&[("TERM", "dumb")]), ColorDepth::Mono);
+        assert_eq!(
+            detect(&[("NO_COLOR", "1"), ("COLORTERM", "truecolor")]),
+            ColorDepth::Mono
+        );
+        // An empty NO_COLOR does not count
+        assert_eq!(
+            detect(&[("NO_COLOR", ""), ("TERM", "xterm-256color")]),
+            ColorDepth::Ansi256
+        );
+    }
+
+    #[test]
+    fn test_convert_256() {
+        let depth = ColorDepth::Ansi256;
+        // Exact cube and grayscale entries map to themselves
+        assert_eq!(depth.convert(Color::Rgb(95, 135, 175)), Color::Indexed(67));
+        assert_eq!(
+            depth.convert(Color::Rgb(128, 128, 128)),
+            Color::Indexed(244)
+        );
+        assert_eq!(depth.convert(Color::Rgb(0, 0, 0)), Color::Indexed(16));
+        // Near matches pick the perceptually closest entry
+        assert_eq!(depth.convert(Color::Rgb(26, 27, 38)), Color::Indexed(234));
+        assert_eq!(depth.convert(Color::Rgb(250, 80, 80)), Color::Indexed(203));
+        // Colors the palette already has are kept
+        assert_eq!(depth.convert(Color::LightRed), Color::LightRed);
+        assert_eq!(depth.convert(Color::Reset), Color::Reset);
+    }
+
+    #[test]
+    fn test_convert_16() {
+        let depth = ColorDepth::Ansi16;
+        assert_eq!(depth.convert(Color::Rgb(10, 10, 20)), Color::Black);
+        assert_eq!(depth.convert(Color::Rgb(250, 250, 250)), Color::White);
+        assert_eq!(depth.convert(Color::Rgb(200, 20, 20)), Color::Red);
+        assert_eq!(depth.convert(Color::Rgb(120, 240, 120)), Color::LightGreen);
+        assert_eq!(depth.convert(Color::Indexed(203)), Color::LightRed);
+        assert_eq!(depth.convert(Color::Indexed(4)), Color::Blue);
+    }
+
+    #[test]
+    fn test_color_rgb() {
+        assert_eq!(color_rgb(Color::Rgb(1, 2, 3)), Some((1, 2, 3)));
+        assert_eq!(color_rgb(Color::Indexed(16)), Some((0, 0, 0)));
+        assert_eq!(color_rgb(Color::Indexed(196)), Some((255, 0, 0)));
+        assert_eq!(color_rgb(Color::LightRed), Some((0xff, 0x55, 0x55)));
+        assert_eq!(color_rgb(Color::Reset), None);
+    }
+
+    #[test]
+    fn test_theme_conversion() {
+        let theme = Theme::default().with_color_depth(ColorDepth::Ansi16);
+        for key in Theme::COLOR_KEYS {
+            let color = theme.color(key).unwrap();
+            assert!(
+                ANSI_16.contains(&color),
+                "{} was not converted: {:?}",
+                key,
+                color
+            );
+        }
+        // Dark comments stay visible on a dark background
+        assert_eq!(theme.background_right, Color::Black);
+        assert_ne!(theme.syntax_comment, Color::Black);
+        // Saturated colors keep their hue instead of collapsing to gray
+        assert_eq!(theme.syntax_function, Color::LightBlue);
+        assert_eq!(theme.syntax_type, Color::Cyan);
+        assert_eq!(theme.syntax_variable, Color::Gray);
+
+        let mono = Theme::default().with_color_depth(ColorDepth::Mono);
+        assert!(Theme::COLOR_KEYS
+            .iter()
+            .all(|key| mono.color(key) == Some(Color::Reset)));
+    }
+}
@@ -0,0 +1,274 @@
+use super::Theme;
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::str::FromStr;
+use toml_edit::{Document, Value};
+
+/// A user theme file: an optional base theme and the colors it overrides
+///
+/// ```toml
+/// extends = "nord"
+/// background_right = "#1e222a"
+/// syntax_keyword = "rgb(180, 142, 173)"
+/// syntax_comment = "dark-gray"
+/// ```
+#[derive(Debug, Default)]
+pub struct ThemeFile {
+    pub extends: Option<String>,
+    colors: Vec<(&'static str, Color)>,
+}
+
+impl ThemeFile {
+    pub fn read(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read theme file: {}", path.display()))?;
+        Self::parse(&contents).with_context(|| format!("Invalid theme file: {}", path.display()))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self> {
+        let document = Document::parse(contents)?;
+        let line = |span: Option<Range<usize>>| {
+            span.map_or(0, |span| contents[..span.start].matches('\n').count() + 1)
+        };
+
+        let mut file = Self::default();
+        for (key, item) in document.as_table().iter() {
+            let (key_repr, _) = document.as_table().get_key_value(key).unwrap();
+            let at = line(key_repr.span());
+
+            if key == "extends" {
+                match item.as_str() {
+                    Some(base) => file.extends = Some(base.to_string()),
+                    None => anyhow::bail!("line {}: `extends` must be a theme name string", at),
+                }
+                continue;
+            }
+
+            let Some(&name) = Theme::COLOR_KEYS.iter().find(|&&name| name == key) else {
+                match suggest(key) {
+                    Some(close) => anyhow::bail!(
+                        "line {}: unknown key `{}` (did you mean `{}`?)",
+                        at,
+                        key,
+                        close
+                    ),
+                    None => anyhow::bail!("line {}: unknown key `{}`", at, key),
+                }
+            };
+
+            match item.as_value().and_then(parse_color) {
+                Some(color) => file.colors.push((name, color)),
+                None => anyhow::bail!(
+                    "line {}: invalid color for `{}`: {} (expected \"#rrggbb\", \"rgb(r, g, b)\", an ANSI color name or a 0-255 palette index)",
+                    line(item.span()).max(at),
+                    key,
+                    item.to_string().trim()
+                ),
+            }
+        }
+        Ok(file)
+    }
+
+    /// Write every color of `theme` as a theme file, preceded by `header` comment lines
+    pub fn render(theme: &Theme, header: &[String]) -> String {
+        let mut output = String::new();
+        for line in header {
+            output.push_str(&format!("# {}\n", line));
+        }
+
+        let mut group = "";
+        for key in Theme::COLOR_KEYS {
+            // A blank line between background_*, editor_*, file_tree_*, ...
+            let prefix = key.split('_').next().unwrap_or(key);
+            if prefix != group {
+                output.push('\n');
+                group = prefix;
+            }
+            if let Some(color) = theme.color(key) {
+                output.push_str(&format!("{} = {}\n", key, format_color(color)));
+            }
+        }
+        output
+    }
+
+    /// Override the colors of `theme` with the ones set in the file
+    pub fn apply(&self, theme: &mut Theme) {
+        for (key, color) in &self.colors {
+            if let Some(slot) = theme.color_mut(key) {
+                *slot = *color;
+            }
+        }
+    }
+}
+
+/// Parse a theme file color: `"#rrggbb"`, `"#rgb"`, `"rgb(r, g, b)"`,
+/// `[r, g, b]`, an ANSI name such as `"light-blue"`, or a palette index
+pub fn parse_color(value: &Value) -> Option<Color> {
+    match value {
+        Value::String(s) => parse_color_str(s.value()),
+        Value::Integer(i) => u8::try_from(*i.value()).ok().map(Color::Indexed),
+        Value::Array(array) => {
+            let channels: Vec<u8> = array
+                .iter()
+                .map(|v| v.as_integer().and_then(|i| u8::try_from(i).ok()))
+                .collect::<Option<_>>()?;
+            match channels[..] {
+                [r, g, b] => Some(Color::Rgb(r, g, b)),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+fn parse_color_str(input: &str) -> Option<Color> {
+    let input = input.trim();
+
+    if let Some(inner) = input
+        .strip_prefix("rgb(")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        let channels: Vec<u8> = inner
+            .split(',')
+            .map(|channel| channel.trim().parse().ok())
+            .collect::<Option<_>>()?;
+        return match channels[..] {
+            [r, g, b] => Some(Color::Rgb(r, g, b)),
+            _ => None,
+        };
+    }
+
+    // Short hex form: #abc -> #aabbcc
+    if let Some(hex) = input.strip_prefix('#').filter(|hex| hex.len() == 3) {
+        let digit = |i: usize| u8::from_str_radix(&hex[i..i + 1], 16).ok().map(|d| d * 17);
+        return Some(Color::Rgb(digit(0)?, digit(1)?, digit(2)?));
+    }
+
+    Color::from_str(input).ok()
+}
+
+fn format_color(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("\"#{:02x}{:02x}{:02x}\"", r, g, b),
+        Color::Indexed(index) => index.to_string(),
+        // Named colors: LightRed -> "light-red"
+        named => {
+            let mut name = String::new();
+            for (i, c) in named.to_string().chars().enumerate() {
+                if c.is_ascii_uppercase() && i > 0 {
+                    name.push('-');
+                }
+                name.push(c.to_ascii_lowercase());
+            }
+            format!("\"{}\"", name)
+        }
+    }
+}
+
+/// The closest known key to a misspelled one, if any is close enough
+fn suggest(key: &str) -> Option<&'static str> {
+    Theme::COLOR_KEYS
+        .iter()
+        .chain(["extends"].iter())
+        .map(|&candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 3)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn color(input: &str) -> Option<Color> {
+        parse_color(&input.parse::<Value>().unwrap())
+    }
+
+    #[test]
+    fn test_parse_color_formats() {
+        assert_eq!(color("\"#1e222a\""), Some(Color::Rgb(30, 34, 42)));
+        assert_eq!(color("\"#fa0\""), Some(Color::Rgb(255, 170, 0)));
+        assert_eq!(color("\"rgb(1, 2, 3)\""), Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(color("[1, 2, 3]"), Some(Color::Rgb(1, 2, 3)));
+        assert_eq!(color("\"light-blue\""), Some(Color::LightBlue));
+        assert_eq!(color("\"bright_red\""), Some(Color::LightRed));
+        assert_eq!(color("208"), Some(Color::Indexed(208)));
+
+        assert_eq!(color("\"#12345\""), None);
+        assert_eq!(color("\"rgb(1, 2)\""), None);
+        assert_eq!(color("[1, 2, 300]"), None);
+        assert_eq!(color("\"purple-ish\""), None);
+        assert_eq!(color("true"), None);
+    }
+
+    #[test]
+    fn test_parse_and_apply() {
+        let file = ThemeFile::parse(
+            "extends = \"nord\"\nsyntax_keyword = \"#ff0000\"\nseparator = \"gray\"\n",
+        )
+        .unwrap();
+        assert_eq!(file.extends.as_deref(), Some("nord"));
+
+        let mut theme = Theme::default();
+        let string_color = theme.syntax_string;
+        file.apply(&mut theme);
+        assert_eq!(theme.syntax_keyword, Color::Rgb(255, 0, 0));
+        assert_eq!(theme.separator, Color::Gray);
+        assert_eq!(theme.syntax_string, string_color);
+    }
+
+    #[test]
+    fn test_render_round_trips() {
+        let theme = Theme {
+            separator: Color::DarkGray,
+            syntax_label: Color::Indexed(141),
+            background_left: Color::Reset,
+            ..Theme::default()
+        };
+
+        let rendered = ThemeFile::render(&theme, &["Imported from test".to_string()]);
+        assert!(rendered.starts_with("# Imported from test\n\nbackground_left = \"reset\"\n"));
+        assert!(rendered.contains("separator = \"dark-gray\"\n"));
+        assert!(rendered.contains("syntax_label = 141\n"));
+
+        let mut parsed = Theme::builtin("nord").unwrap();
+        ThemeFile::parse(&rendered).unwrap().apply(&mut parsed);
+        assert_eq!(format!("{:?}", parsed), format!("{:?}", theme));
+    }
+
+    #[test]
+    fn test_errors_point_to_the_key() {
+        let error = ThemeFile::parse("syntax_keyword = \"#ff0000\"\nsintax_string = \"red\"\n")
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            "line 2: unknown key `sintax_string` (did you mean `syntax_string`?)"
+        );
+
+        let error = ThemeFile::parse("\n\nseparator = \"#12\"\n")
+            .unwrap_err()
+            .to_string();
+        assert!(error.starts_with("line 3: invalid color for `separator`: \"#12\""));
+
+        assert!(ThemeFile::parse("extends = 1\n").is_err());
+        assert!(ThemeFile::parse("separator = \n").is_err());
+    }
+}
@@ -0,0 +1,707 @@
+use super::{themes, Theme};
+use anyhow::{Context, Result};
+use ratatui::style::Color;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Color scheme formats that can be converted into a gitlogue theme
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchemeFormat {
+    VsCode,
+    Base16,
+    Iterm2,
+}
+
+impl SchemeFormat {
+    /// Guess the format from the file extension, then from the contents
+    pub fn detect(path: &Path, contents: &[u8]) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json" | "jsonc") => return Self::VsCode,
+            Some("yaml" | "yml") => return Self::Base16,
+            Some("itermcolors" | "plist") => return Self::Iterm2,
+            _ => {}
+        }
+
+        let start = String::from_utf8_lossy(&contents[..contents.len().min(64)]);
+        let start = start.trim_start();
+        if start.starts_with("bplist") || start.starts_with("<?xml") || start.starts_with("<plist")
+        {
+            Self::Iterm2
+        } else if start.starts_with('{') {
+            Self::VsCode
+        } else {
+            Self::Base16
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::VsCode => "VS Code theme",
+            Self::Base16 => "Base16 scheme",
+            Self::Iterm2 => "iTerm2 color preset",
+        }
+    }
+}
+
+/// A theme converted from another tool's color scheme
+#[derive(Debug, Clone)]
+pub struct ImportedTheme {
+    /// Theme name derived from the scheme's own name, or else its file name
+    pub name: String,
+    pub format: SchemeFormat,
+    pub theme: Theme,
+}
+
+/// Convert the color scheme in `path` into a theme
+pub fn from_file(path: &Path) -> Result<ImportedTheme> {
+    let contents = fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let format = SchemeFormat::detect(path, &contents);
+    let parsed = match format {
+        SchemeFormat::VsCode => from_vscode(&String::from_utf8_lossy(&contents)),
+        SchemeFormat::Base16 => from_base16(&String::from_utf8_lossy(&contents)),
+        SchemeFormat::Iterm2 => from_iterm2(&contents),
+    };
+    let (name, theme) = parsed
+        .with_context(|| format!("Failed to import {} as {}", path.display(), format.label()))?;
+
+    let stem = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("");
+    let name = [name.as_deref().unwrap_or(""), stem, "imported"]
+        .into_iter()
+        .map(slug)
+        .find(|name| !name.is_empty())
+        .unwrap_or_default();
+    Ok(ImportedTheme {
+        name,
+        format,
+        theme,
+    })
+}
+
+/// Lowercase a scheme name into a theme name: "Tomorrow Night (Eighties)" -> "tomorrow-night-eighties"
+fn slug(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .map(|part| part.to_ascii_lowercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// The sixteen colors of a Base16 scheme, `base00` to `base0F`
+///
+/// base00-07 run from the background to the brightest foreground;
+/// base08-0F are red, orange, yellow, green, cyan, blue, magenta and brown.
+#[derive(Debug, Clone, Copy)]
+struct Palette([Rgb; 16]);
+
+impl Palette {
+    fn to_theme(self) -> Theme {
+        let base = |index: usize| self.0[index].color();
+        Theme {
+            background_left: self.0[0].blend(self.0[1], 0.5).color(),
+            background_right: base(0x00),
+
+            editor_line_number: base(0x03),
+            editor_line_number_cursor: base(0x0D),
+            editor_separator: base(0x02),
+            editor_cursor_char_bg: base(0x0D),
+            editor_cursor_char_fg: base(0x00),
+            editor_cursor_line_bg: base(0x01),
+
+            file_tree_added: base(0x0B),
+            file_tree_deleted: base(0x08),
+            file_tree_modified: base(0x0A),
+            file_tree_renamed: base(0x0D),
+            file_tree_directory: base(0x0D),
+            file_tree_current_file_bg: base(0x02),
+            file_tree_current_file_fg: base(0x06),
+            file_tree_default: base(0x05),
+            file_tree_stats_added: base(0x0B),
+            file_tree_stats_deleted: base(0x08),
+
+            terminal_command: base(0x06),
+            terminal_output: base(0x04),
+            terminal_cursor_bg: base(0x0D),
+            terminal_cursor_fg: base(0x00),
+
+            status_hash: base(0x0A),
+            status_author: base(0x0B),
+            status_date: base(0x0D),
+            status_message: base(0x06),
+            status_no_commit: base(0x03),
+
+            separator: base(0x02),
+
+            syntax_keyword: base(0x0E),
+            syntax_type: base(0x0A),
+            syntax_function: base(0x0D),
+            syntax_variable: base(0x08),
+            syntax_string: base(0x0B),
+            syntax_number: base(0x09),
+            syntax_comment: base(0x03),
+            syntax_operator: base(0x05),
+            syntax_punctuation: base(0x05),
+            syntax_constant: base(0x09),
+            syntax_parameter: base(0x08),
+            syntax_property: base(0x0C),
+            syntax_label: base(0x0A),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Base16Scheme {
+    #[serde(alias = "scheme")]
+    name: Option<String>,
+    /// Newer (tinted-theming) schemes nest the colors under `palette`
+    palette: Option<HashMap<String, String>>,
+    #[serde(flatten)]
+    rest: HashMap<String, serde_yaml::Value>,
+}
+
+/// Convert a Base16 YAML scheme, in the classic flat layout or with a `palette` table
+pub fn from_base16(yaml: &str) -> Result<(Option<String>, Theme)> {
+    let scheme: Base16Scheme = serde_yaml::from_str(yaml).context("Invalid YAML")?;
+    let colors: HashMap<String, String> = match scheme.palette {
+        Some(palette) => palette,
+        None => scheme
+            .rest
+            .into_iter()
+            .filter_map(|(key, value)| Some((key, value.as_str()?.to_string())))
+            .collect(),
+    };
+
+    let mut palette = [Rgb::default(); 16];
+    for (index, slot) in palette.iter_mut().enumerate() {
+        let key = format!("base{:02X}", index);
+        let value = colors
+            .get(&key)
+            .or_else(|| colors.get(&key.to_lowercase()))
+            .with_context(|| format!("Missing color {}", key))?;
+        *slot = Rgb::parse_hex(value)
+            .map(|(rgb, _)| rgb)
+            .with_context(|| format!("Invalid color for {}: {}", key, value))?;
+    }
+    Ok((scheme.name, Palette(palette).to_theme()))
+}
+
+#[derive(Deserialize)]
+struct ItermColor {
+    #[serde(rename = "Red Component")]
+    red: f64,
+    #[serde(rename = "Green Component")]
+    green: f64,
+    #[serde(rename = "Blue Component")]
+    blue: f64,
+}
+
+impl ItermColor {
+    fn rgb(&self) -> Rgb {
+        let channel = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+        Rgb(channel(self.red), channel(self.green), channel(self.blue))
+    }
+}
+
+/// Convert an iTerm2 `.itermcolors` preset (XML or binary plist)
+///
+/// The sixteen ANSI colors and the background/foreground are arranged into a
+/// Base16 palette; the cursor colors are used as they are.
+pub fn from_iterm2(data: &[u8]) -> Result<(Option<String>, Theme)> {
+    let colors: HashMap<String, ItermColor> =
+        plist::from_bytes(data).context("Invalid property list")?;
+    let get = |key: &str| {
+        colors
+            .get(key)
+            .map(ItermColor::rgb)
+            .with_context(|| format!("Missing color: {}", key))
+    };
+    let ansi = |index: usize| get(&format!("Ansi {} Color", index));
+
+    let background = get("Background Color")?;
+    let foreground = get("Foreground Color")?;
+    let selection = colors
+        .get("Selection Color")
+        .map(ItermColor::rgb)
+        .unwrap_or_else(|| background.blend(foreground, 0.2));
+
+    let palette = Palette([
+        background,
+        background.blend(foreground, 0.08),
+        selection,
+        ansi(8)?,
+        background.blend(foreground, 0.6),
+        foreground,
+        foreground.blend(ansi(15)?, 0.5),
+        ansi(15)?,
+        ansi(1)?,
+        ansi(1)?.blend(ansi(3)?, 0.5),
+        ansi(3)?,
+        ansi(2)?,
+        ansi(6)?,
+        ansi(4)?,
+        ansi(5)?,
+        ansi(9)?,
+    ]);
+
+    let mut theme = palette.to_theme();
+    if let Some(cursor) = colors.get("Cursor Color") {
+        theme.editor_cursor_char_bg = cursor.rgb().color();
+        theme.terminal_cursor_bg = cursor.rgb().color();
+    }
+    if let Some(cursor_text) = colors.get("Cursor Text Color") {
+        theme.editor_cursor_char_fg = cursor_text.rgb().color();
+        theme.terminal_cursor_fg = cursor_text.rgb().color();
+    }
+    Ok((None, theme))
+}
+
+#[derive(Deserialize)]
+struct VsCodeTheme {
+    name: Option<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+    #[serde(default, rename = "tokenColors")]
+    token_colors: Vec<TokenColor>,
+}
+
+#[derive(Deserialize)]
+struct TokenColor {
+    scope: Option<Scope>,
+    #[serde(default)]
+    settings: TokenSettings,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Scope {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize, Default)]
+struct TokenSettings {
+    foreground: Option<String>,
+}
+
+/// Token scopes looked up for each syntax color, most specific first
+const SYNTAX_SCOPES: &[(&str, &[&str])] = &[
+    (
+        "syntax_keyword",
+        &["keyword.control", "keyword", "storage.type"],
+    ),
+    (
+        "syntax_type",
+        &["entity.name.type", "support.type", "storage.type"],
+    ),
+    (
+        "syntax_function",
+        &["entity.name.function", "support.function"],
+    ),
+    ("syntax_variable", &["variable.other", "variable"]),
+    ("syntax_string", &["string.quoted", "string"]),
+    ("syntax_number", &["constant.numeric", "constant"]),
+    ("syntax_comment", &["comment.line", "comment"]),
+    ("syntax_operator", &["keyword.operator", "keyword"]),
+    (
+        "syntax_punctuation",
+        &["punctuation.separator", "punctuation"],
+    ),
+    ("syntax_constant", &["constant.language", "constant"]),
+    ("syntax_parameter", &["variable.parameter", "variable"]),
+    (
+        "syntax_property",
+        &[
+            "variable.other.property",
+            "support.type.property-name",
+            "variable.other.member",
+        ],
+    ),
+    ("syntax_label", &["entity.name.label", "entity.name.tag"]),
+];
+
+/// Workbench colors looked up for each pane color, in order of preference
+const WORKBENCH_KEYS: &[(&str, &[&str])] = &[
+    ("background_right", &["editor.background"]),
+    (
+        "background_left",
+        &["sideBar.background", "editor.background"],
+    ),
+    ("editor_line_number", &["editorLineNumber.foreground"]),
+    (
+        "editor_line_number_cursor",
+        &["editorLineNumber.activeForeground", "editor.foreground"],
+    ),
+    (
+        "editor_separator",
+        &["editorIndentGuide.background", "editorGroup.border"],
+    ),
+    ("editor_cursor_char_bg", &["editorCursor.foreground"]),
+    (
+        "editor_cursor_char_fg",
+        &["editorCursor.background", "editor.background"],
+    ),
+    ("editor_cursor_line_bg", &["editor.lineHighlightBackground"]),
+    (
+        "file_tree_added",
+        &[
+            "gitDecoration.addedResourceForeground",
+            "gitDecoration.untrackedResourceForeground",
+        ],
+    ),
+    (
+        "file_tree_deleted",
+        &["gitDecoration.deletedResourceForeground"],
+    ),
+    (
+        "file_tree_modified",
+        &["gitDecoration.modifiedResourceForeground"],
+    ),
+    (
+        "file_tree_renamed",
+        &[
+            "gitDecoration.renamedResourceForeground",
+            "gitDecoration.untrackedResourceForeground",
+        ],
+    ),
+    (
+        "file_tree_directory",
+        &["symbolIcon.folderForeground", "textLink.foreground"],
+    ),
+    (
+        "file_tree_current_file_bg",
+        &[
+            "list.activeSelectionBackground",
+            "editor.selectionBackground",
+        ],
+    ),
+    (
+        "file_tree_current_file_fg",
+        &["list.activeSelectionForeground", "editor.foreground"],
+    ),
+    (
+        "file_tree_default",
+        &["sideBar.foreground", "foreground", "editor.foreground"],
+    ),
+    (
+        "file_tree_stats_added",
+        &[
+            "gitDecoration.addedResourceForeground",
+            "terminal.ansiGreen",
+        ],
+    ),
+    (
+        "file_tree_stats_deleted",
+        &[
+            "gitDecoration.deletedResourceForeground",
+            "terminal.ansiRed",
+        ],
+    ),
+    (
+        "terminal_command",
+        &["terminal.foreground", "editor.foreground"],
+    ),
+    (
+        "terminal_output",
+        &["terminal.ansiBrightBlack", "editorLineNumber.foreground"],
+    ),
+    (
+        "terminal_cursor_bg",
+        &["terminalCursor.foreground", "editorCursor.foreground"],
+    ),
+    (
+        "terminal_cursor_fg",
+        &[
+            "terminalCursor.background",
+            "terminal.background",
+            "editor.background",
+        ],
+    ),
+    ("status_hash", &["terminal.ansiYellow"]),
+    ("status_author", &["terminal.ansiGreen"]),
+    ("status_date", &["terminal.ansiBlue"]),
+    ("status_message", &["editor.foreground"]),
+    ("status_no_commit", &["editorLineNumber.foreground"]),
+    (
+        "separator",
+        &["editorGroup.border", "panel.border", "sideBar.border"],
+    ),
+];
+
+/// Convert a VS Code color theme (JSON with comments)
+///
+/// Workbench `colors` map to the panes and `tokenColors` scopes to the
+/// syntax colors. Anything the theme leaves out comes from `tokyo-night`,
+/// or `solarized-light` for light themes.
+pub fn from_vscode(json: &str) -> Result<(Option<String>, Theme)> {
+    let vscode: VsCodeTheme =
+        serde_json::from_str(&strip_jsonc(json)).context("Invalid theme JSON")?;
+
+    let mut theme = match vscode.kind.as_deref() {
+        Some("light") => themes::solarized_light(),
+        _ => Theme::default(),
+    };
+
+    // Translucent colors are blended over the editor background
+    let background = vscode
+        .colors
+        .get("editor.background")
+        .and_then(|value| Rgb::parse_hex(value))
+        .map(|(rgb, _)| rgb);
+    let resolve = |value: &str| {
+        let (rgb, alpha) = Rgb::parse_hex(value)?;
+        Some(match (alpha, background) {
+            (Some(alpha), Some(background)) => background.blend(rgb, alpha as f64 / 255.0),
+            _ => rgb,
+        })
+    };
+
+    for (key, candidates) in WORKBENCH_KEYS {
+        let color = candidates
+            .iter()
+            .find_map(|name| vscode.colors.get(*name).and_then(|value| resolve(value)));
+        if let (Some(color), Some(slot)) = (color, theme.color_mut(key)) {
+            *slot = color.color();
+        }
+    }
+
+    let rules = TokenRules::new(&vscode.token_colors);
+    for (key, scopes) in SYNTAX_SCOPES {
+        let color = scopes
+            .iter()
+            .find_map(|scope| rules.lookup(scope))
+            .and_then(resolve);
+        if let (Some(color), Some(slot)) = (color, theme.color_mut(key)) {
+            *slot = color.color();
+        }
+    }
+
+    Ok((vscode.name, theme))
+}
+
+/// TextMate scope selectors with a foreground color, in file order
+struct TokenRules<'a>(Vec<(&'a str, &'a str)>);
+
+impl<'a> TokenRules<'a> {
+    fn new(token_colors: &'a [TokenColor]) -> Self {
+        let mut rules = Vec::new();
+        for token in token_colors {
+            let (Some(scope), Some(foreground)) = (&token.scope, &token.settings.foreground) else {
+                continue;
+            };
+            let selectors: Vec<&str> = match scope {
+                Scope::One(scope) => scope.split(',').collect(),
+                Scope::Many(scopes) => scopes.iter().flat_map(|s| s.split(',')).collect(),
+            };
+            for selector in selectors.into_iter().map(str::trim) {
+                // Descendant selectors and exclusions only apply in context
+                if !selector.is_empty() && !selector.contains(' ') {
+                    rules.push((selector, foreground.as_str()));
+                }
+            }
+        }
+        Self(rules)
+    }
+
+    /// Foreground of the most specific selector matching `scope`,
+    /// the later one winning among equally specific selectors
+    fn lookup(&self, scope: &str) -> Option<&'a str> {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|(_, (selector, _))| {
+                scope == *selector
+                    || scope
+                        .strip_prefix(selector)
+                        .is_some_and(|rest| rest.starts_with('.'))
+            })
+            .max_by_key(|(index, (selector, _))| (selector.len(), *index))
+            .map(|(_, (_, foreground))| *foreground)
+    }
+}
+
+/// Remove `//` and `/* */` comments and trailing commas, which VS Code allows
+fn strip_jsonc(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => output.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().is_some_and(|&next| next != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for next in chars.by_ref() {
+                    if previous == '*' && next == '/' {
+                        break;
+                    }
+                    previous = next;
+                }
+            }
+            (']' | '}', _) => {
+                let trimmed = output.trim_end().len();
+                if output[..trimmed].ends_with(',') {
+                    output.remove(trimmed - 1);
+                }
+                output.push(c);
+            }
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Rgb(u8, u8, u8);
+
+impl Rgb {
+    /// Parse `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa` (the `#` is optional)
+    fn parse_hex(value: &str) -> Option<(Self, Option<u8>)> {
+        let hex = value.trim().trim_start_matches('#');
+        if !hex.is_ascii() {
+            return None;
+        }
+        let digits: Vec<u8> = match hex.len() {
+            3 | 4 => hex
+                .chars()
+                .map(|c| c.to_digit(16).map(|d| d as u8 * 17))
+                .collect::<Option<_>>()?,
+            6 | 8 => (0..hex.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+                .collect::<Option<_>>()?,
+            _ => return None,
+        };
+        Some((
+            Self(digits[0], digits[1], digits[2]),
+            digits.get(3).copied(),
+        ))
+    }
+
+    /// Mix `amount` (0.0-1.0) of `other` into this color
+    fn blend(self, other: Self, amount: f64) -> Self {
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * amount).round() as u8;
+        Self(
+            mix(self.0, other.0),
+            mix(self.1, other.1),
+            mix(self.2, other.2),
+        )
+    }
+
+    fn color(self) -> Color {
+        Color::Rgb(self.0, self.1, self.2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base16_fixture() {
+        let (name, theme) = from_base16(include_str!("fixtures/base16-ocean.yaml")).unwrap();
+        assert_eq!(name.as_deref(), Some("Ocean"));
+        assert_eq!(theme.background_right, Color::Rgb(0x2b, 0x30, 0x3b));
+        assert_eq!(theme.background_left, Color::Rgb(0x30, 0x37, 0x41));
+        assert_eq!(theme.syntax_keyword, Color::Rgb(0xb4, 0x8e, 0xad));
+        assert_eq!(theme.syntax_string, Color::Rgb(0xa3, 0xbe, 0x8c));
+        assert_eq!(theme.syntax_comment, Color::Rgb(0x65, 0x73, 0x7e));
+        assert_eq!(theme.file_tree_deleted, Color::Rgb(0xbf, 0x61, 0x6a));
+
+        // The tinted-theming layout maps the same way
+        let (name, nested) =
+            from_base16(include_str!("fixtures/base16-ocean-palette.yaml")).unwrap();
+        assert_eq!(name.as_deref(), Some("Ocean"));
+        assert_eq!(format!("{:?}", nested), format!("{:?}", theme));
+
+        assert!(from_base16("scheme: Broken\nbase00: \"2b303b\"\n").is_err());
+    }
+
+    #[test]
+    fn test_iterm2_fixture() {
+        let (name, theme) = from_iterm2(include_bytes!("fixtures/iterm2.itermcolors")).unwrap();
+        assert_eq!(name, None);
+        assert_eq!(theme.background_right, Color::Rgb(0x28, 0x2a, 0x36));
+        assert_eq!(theme.file_tree_default, Color::Rgb(0xf8, 0xf8, 0xf2));
+        assert_eq!(theme.syntax_keyword, Color::Rgb(0xff, 0x79, 0xc6));
+        assert_eq!(theme.syntax_string, Color::Rgb(0x50, 0xfa, 0x7b));
+        assert_eq!(theme.syntax_comment, Color::Rgb(0x62, 0x72, 0xa4));
+        assert_eq!(theme.editor_cursor_char_bg, Color::Rgb(0xf8, 0xf8, 0xf2));
+        assert_eq!(theme.terminal_cursor_fg, Color::Rgb(0x28, 0x2a, 0x36));
+    }
+
+    #[test]
+    fn test_vscode_fixture() {
+        let (name, theme) = from_vscode(include_str!("fixtures/vscode-theme.json")).unwrap();
+        assert_eq!(name.as_deref(), Some("Harbor Night"));
+        assert_eq!(theme.background_right, Color::Rgb(0x1b, 0x1e, 0x28));
+        assert_eq!(theme.background_left, Color::Rgb(0x15, 0x17, 0x1f));
+        // #ffffff10 blended over the editor background
+        assert_eq!(theme.editor_cursor_line_bg, Color::Rgb(0x29, 0x2c, 0x35));
+        assert_eq!(theme.file_tree_added, Color::Rgb(0x7e, 0xc6, 0x99));
+
+        // keyword.control is more specific than keyword
+        assert_eq!(theme.syntax_keyword, Color::Rgb(0xc5, 0x94, 0xc5));
+        assert_eq!(theme.syntax_operator, Color::Rgb(0x5f, 0xb3, 0xb3));
+        // The later of two equally specific rules wins
+        assert_eq!(theme.syntax_string, Color::Rgb(0x99, 0xc7, 0x94));
+        assert_eq!(theme.syntax_function, Color::Rgb(0x66, 0x99, 0xcc));
+        assert_eq!(theme.syntax_comment, Color::Rgb(0x65, 0x73, 0x7e));
+        // Scopes the theme does not style keep the base theme's colors
+        assert_eq!(theme.syntax_label, Theme::default().syntax_label);
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Tomorrow Night (Eighties)"), "tomorrow-night-eighties");
+        assert_eq!(slug("Ocean"), "ocean");
+        assert_eq!(slug("  ~~ "), "");
+    }
+
+    #[test]
+    fn test_strip_jsonc() {
+        assert_eq!(
+            strip_jsonc("{\n  // note\n  \"a\": \"//x\", /* y */ \"b\": [1, 2,],\n}"),
+            "{\n  \n  \"a\": \"//x\",  \"b\": [1, 2]\n}"
+        );
+    }
+
+    #[test]
+    fn test_detect_format() {
+        let detect =
+            |name: &str, contents: &str| SchemeFormat::detect(Path::new(name), contents.as_bytes());
+        assert_eq!(detect("a.json", ""), SchemeFormat::VsCode);
+        assert_eq!(detect("a.yml", ""), SchemeFormat::Base16);
+        assert_eq!(detect("Dracula.itermcolors", ""), SchemeFormat::Iterm2);
+        assert_eq!(
+            detect("theme", "<?xml version=\"1.0\"?>"),
+            SchemeFormat::Iterm2
+        );
+        assert_eq!(detect("theme", "  {\"name\": \"x\"}"), SchemeFormat::VsCode);
+        assert_eq!(detect("theme", "scheme: x"), SchemeFormat::Base16);
+    }
+}
@@ -1,64 +1,102 @@
+mod depth;
+mod file;
+pub mod import;
 mod themes;
 
+pub use depth::{color_rgb, ColorDepth};
+pub use file::ThemeFile;
+
 use anyhow::{Context, Result};
-use ratatui::style::Color;
+use ratatui::style::{Color, Modifier, Style};
+use std::fs;
+use std::path::Path;
+
+/// Defines `Theme` along with lookups of its colors by field name,
+/// which is how theme files refer to them
+macro_rules! theme_colors {
+    ($($field:ident),* $(,)?) => {
+        #[derive(Debug, Clone)]
+        pub struct Theme {
+            $(pub $field: Color,)*
+        }
+
+        impl Theme {
+            /// Names of all colors, as used for keys in theme files
+            pub const COLOR_KEYS: &'static [&'static str] = &[$(stringify!($field)),*];
+
+            /// Look up a color by its key
+            pub fn color(&self, key: &str) -> Option<Color> {
+                match key {
+                    $(stringify!($field) => Some(self.$field),)*
+                    _ => None,
+                }
+            }
+
+            fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+                match key {
+                    $(stringify!($field) => Some(&mut self.$field),)*
+                    _ => None,
+                }
+            }
+        }
+    };
+}
 
-#[derive(Debug, Clone)]
-pub struct Theme {
+theme_colors! {
     // Background colors
-    pub background_left: Color,  // FileTree and StatusBar side (darker)
-    pub background_right: Color, // Editor and Terminal side
+    background_left,  // FileTree and StatusBar side (darker)
+    background_right, // Editor and Terminal side
 
     // Editor colors
-    pub editor_line_number: Color,
-    pub editor_line_number_cursor: Color,
-    pub editor_separator: Color,
-    pub editor_cursor_char_bg: Color,
-    pub editor_cursor_char_fg: Color,
-    pub editor_cursor_line_bg: Color,
+    editor_line_number,
+    editor_line_number_cursor,
+    editor_separator,
+    editor_cursor_char_bg,
+    editor_cursor_char_fg,
+    editor_cursor_line_bg,
 
     // File tree colors
-    pub file_tree_added: Color,
-    pub file_tree_deleted: Color,
-    pub file_tree_modified: Color,
-    pub file_tree_renamed: Color,
-    pub file_tree_directory: Color,
-    pub file_tree_current_file_bg: Color,
-    pub file_tree_current_file_fg: Color,
-    pub file_tree_default: Color,
-    pub file_tree_stats_added: Color,
-    pub file_tree_stats_deleted: Color,
+    file_tree_added,
+    file_tree_deleted,
+    file_tree_modified,
+    file_tree_renamed,
+    file_tree_directory,
+    file_tree_current_file_bg,
+    file_tree_current_file_fg,
+    file_tree_default,
+    file_tree_stats_added,
+    file_tree_stats_deleted,
 
     // Terminal colors
-    pub terminal_command: Color,
-    pub terminal_output: Color,
-    pub terminal_cursor_bg: Color,
-    pub terminal_cursor_fg: Color,
+    terminal_command,
+    terminal_output,
+    terminal_cursor_bg,
+    terminal_cursor_fg,
 
     // Status bar colors
-    pub status_hash: Color,
-    pub status_author: Color,
-    pub status_date: Color,
-    pub status_message: Color,
-    pub status_no_commit: Color,
+    status_hash,
+    status_author,
+    status_date,
+    status_message,
+    status_no_commit,
 
     // Separator colors
-    pub separator: Color,
+    separator,
 
     // Syntax highlighting colors
-    pub syntax_keyword: Color,
-    pub syntax_type: Color,
-    pub syntax_function: Color,
-    pub syntax_variable: Color,
-    pub syntax_string: Color,
-    pub syntax_number: Color,
-    pub syntax_comment: Color,
-    pub syntax_operator: Color,
-    pub syntax_punctuation: Color,
-    pub syntax_constant: Color,
-    pub syntax_parameter: Color,
-    pub syntax_property: Color,
-    pub syntax_label: Color,
+    syntax_keyword,
+    syntax_type,
+    syntax_function,
+    syntax_variable,
+    syntax_string,
+    syntax_number,
+    syntax_comment,
+    syntax_operator,
+    syntax_punctuation,
+    syntax_constant,
+    syntax_parameter,
+    syntax_property,
+    syntax_label,
 }
 
 impl Default for Theme {
@@ -68,26 +106,95 @@ impl Default for Theme {
 }
 
 impl Theme {
-    /// Load theme by name
-    pub fn load(name: &str) -> Result<Self> {
+    /// Load theme by name, looking for `<name>.toml` in `user_dir` before the built-in themes
+    pub fn load(name: &str, user_dir: Option<&Path>) -> Result<Self> {
+        Self::resolve(name, user_dir, &mut Vec::new())
+    }
+
+    /// `chain` holds the user themes being loaded, so that a user theme
+    /// extending the built-in theme of the same name does not load itself
+    fn resolve(name: &str, user_dir: Option<&Path>, chain: &mut Vec<String>) -> Result<Self> {
+        let user_file = user_dir
+            .map(|dir| dir.join(format!("{}.toml", name)))
+            .filter(|path| path.is_file());
+
+        match user_file {
+            Some(path) if !chain.iter().any(|loaded| loaded == name) => {
+                chain.push(name.to_string());
+                let file = ThemeFile::read(&path)?;
+                let mut theme = match &file.extends {
+                    Some(base) => Self::resolve(base, user_dir, chain).with_context(|| {
+                        format!("Failed to load base theme of {}", path.display())
+                    })?,
+                    None => Self::default(),
+                };
+                file.apply(&mut theme);
+                Ok(theme)
+            }
+            _ => match Self::builtin(name) {
+                Some(theme) => Ok(theme),
+                None if chain.iter().any(|loaded| loaded == name) => anyhow::bail!(
+                    "Theme inheritance cycle: {} -> {}",
+                    chain.join(" -> "),
+                    name
+                ),
+                None => {
+                    let mut available: Vec<String> = Self::available_themes()
+                        .into_iter()
+                        .map(String::from)
+                        .collect();
+                    available.extend(user_dir.map(Self::user_themes).unwrap_or_default());
+                    Err(anyhow::anyhow!("Unknown theme: {}", name))
+                        .context(format!("Available themes: {}", available.join(", ")))
+                }
+            },
+        }
+    }
+
+    fn builtin(name: &str) -> Option<Self> {
         match name {
-            "ayu-dark" => Ok(themes::ayu_dark()),
-            "catppuccin" => Ok(themes::catppuccin()),
-            "dracula" => Ok(themes::dracula()),
-            "everforest" => Ok(themes::everforest()),
-            "github-dark" => Ok(themes::github_dark()),
-            "gruvbox" => Ok(themes::gruvbox()),
-            "material" => Ok(themes::material()),
-            "monokai" => Ok(themes::monokai()),
-            "night-owl" => Ok(themes::night_owl()),
-            "nord" => Ok(themes::nord()),
-            "one-dark" => Ok(themes::one_dark()),
-            "rose-pine" => Ok(themes::rose_pine()),
-            "solarized-dark" => Ok(themes::solarized_dark()),
-            "solarized-light" => Ok(themes::solarized_light()),
-            "tokyo-night" => Ok(themes::tokyo_night()),
-            _ => Err(anyhow::anyhow!("Unknown theme: {}", name))
-                .context("Available themes: ayu-dark, catppuccin, dracula, everforest, github-dark, gruvbox, material, monokai, night-owl, nord, one-dark, rose-pine, solarized-dark, solarized-light, tokyo-night"),
+            "ayu-dark" => Some(themes::ayu_dark()),
+            "catppuccin" => Some(themes::catppuccin()),
+            "dracula" => Some(themes::dracula()),
+            "everforest" => Some(themes::everforest()),
+            "github-dark" => Some(themes::github_dark()),
+            "gruvbox" => Some(themes::gruvbox()),
+            "material" => Some(themes::material()),
+            "monokai" => Some(themes::monokai()),
+            "night-owl" => Some(themes::night_owl()),
+            "nord" => Some(themes::nord()),
+            "one-dark" => Some(themes::one_dark()),
+            "rose-pine" => Some(themes::rose_pine()),
+            "solarized-dark" => Some(themes::solarized_dark()),
+            "solarized-light" => Some(themes::solarized_light()),
+            "tokyo-night" => Some(themes::tokyo_night()),
+            _ => None,
+        }
+    }
+
+    /// Names of the theme files in `dir`, sorted
+    pub fn user_themes(dir: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| path.file_stem()?.to_str().map(String::from))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Style of a cursor or selection drawn with `bg` and `fg`
+    ///
+    /// Without a background color (e.g. in monochrome mode) it is drawn in
+    /// reverse video so that it stays visible.
+    pub fn highlight(bg: Color, fg: Color) -> Style {
+        if bg == Color::Reset {
+            Style::default().add_modifier(Modifier::REVERSED)
+        } else {
+            Style::default().bg(bg).fg(fg)
         }
     }
 
@@ -119,3 +226,45 @@ impl Theme {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_themes_extend_and_shadow_builtins() {
+        let dir = std::env::temp_dir().join(format!("gitlogue-themes-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("nord.toml"),
+            "extends = \"nord\"\nsyntax_keyword = \"red\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("mine.toml"), "extends = \"nord\"\nseparator = 8\n").unwrap();
+        fs::write(dir.join("loop-a.toml"), "extends = \"loop-b\"\n").unwrap();
+        fs::write(dir.join("loop-b.toml"), "extends = \"loop-a\"\n").unwrap();
+
+        let builtin = Theme::builtin("nord").unwrap();
+        let mine = Theme::load("mine", Some(&dir)).unwrap();
+        assert_eq!(mine.syntax_keyword, Color::Red);
+        assert_eq!(mine.separator, Color::Indexed(8));
+        assert_eq!(mine.background_left, builtin.background_left);
+
+        // A user theme named like a built-in one replaces it and can extend it
+        let shadowed = Theme::load("nord", Some(&dir)).unwrap();
+        assert_eq!(shadowed.syntax_keyword, Color::Red);
+        assert_eq!(shadowed.syntax_string, builtin.syntax_string);
+
+        let error = format!("{:#}", Theme::load("loop-a", Some(&dir)).unwrap_err());
+        assert!(error.contains("Theme inheritance cycle: loop-a -> loop-b -> loop-a"));
+
+        let error = format!("{:#}", Theme::load("missing", Some(&dir)).unwrap_err());
+        assert!(error.contains("loop-a, loop-b, mine, nord"));
+
+        assert_eq!(
+            Theme::user_themes(&dir),
+            vec!["loop-a", "loop-b", "mine", "nord"]
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,237 @@
+use anyhow::Result;
+use chrono::{Local, NaiveTime};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::config::ThemeSchedule;
+use crate::theme::{ColorDepth, Theme};
+
+// How often the themes directory and the day/night schedule are checked
+const CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Builds the active theme and rebuilds it when switching themes, when
+/// theme files change on disk, or when day turns to night
+pub struct ThemeSwitcher {
+    name: String,
+    user_dir: Option<PathBuf>,
+    background: bool,
+    color_depth: ColorDepth,
+    next_check: Instant,
+    /// Modification times of the user theme files as of the last check (watch mode)
+    stamps: Option<Vec<(PathBuf, Option<SystemTime>)>>,
+    /// Day/night themes and the one picked at the last check
+    schedule: Option<(ThemeSchedule, String)>,
+}
+
+impl ThemeSwitcher {
+    pub fn new(
+        name: &str,
+        user_dir: Option<PathBuf>,
+        background: bool,
+        color_depth: ColorDepth,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            user_dir,
+            background,
+            color_depth,
+            next_check: Instant::now() + CHECK_INTERVAL,
+            stamps: None,
+            schedule: None,
+        }
+    }
+
+    /// Reload the theme whenever a file in the themes directory changes
+    pub fn watch(mut self) -> Self {
+        self.stamps = Some(self.read_stamps());
+        self
+    }
+
+    /// Follow the day/night schedule instead of a fixed theme
+    pub fn schedule(mut self, schedule: ThemeSchedule) -> Self {
+        self.name = schedule.theme_at(Local::now().time()).to_string();
+        self.schedule = Some((schedule, self.name.clone()));
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Build the current theme with the background and color depth settings applied
+    pub fn load(&self) -> Result<Theme> {
+        let mut theme = Theme::load(&self.name, self.user_dir.as_deref())?;
+        if !self.background {
+            theme = theme.with_transparent_background();
+        }
+        Ok(theme.with_color_depth(self.color_depth))
+    }
+
+    /// Switch to the next (or previous) built-in or user theme
+    pub fn cycle(&mut self, forward: bool) -> Result<Theme> {
+        let names = self.names();
+        let next = match names.iter().position(|name| *name == self.name) {
+            Some(index) if forward => (index + 1) % names.len(),
+            Some(index) => (index + names.len() - 1) % names.len(),
+            None => 0,
+        };
+        self.name = names[next].clone();
+        self.load()
+    }
+
+    /// The rebuilt theme if theme files changed or the scheduled theme
+    /// switched since the last check
+    pub fn poll_changes(&mut self) -> Option<Result<Theme>> {
+        let now = Instant::now();
+        if self.next_check > now {
+            return None;
+        }
+        self.next_check = now + CHECK_INTERVAL;
+
+        let scheduled = self.poll_schedule(Local::now().time());
+        let modified = self.poll_files();
+        (scheduled || modified).then(|| self.load())
+    }
+
+    // A theme picked with `t` stays until the schedule switches to another one
+    fn poll_schedule(&mut self, time: NaiveTime) -> bool {
+        let Some((schedule, current)) = self.schedule.as_mut() else {
+            return false;
+        };
+        let scheduled = schedule.theme_at(time);
+        if scheduled == current {
+            return false;
+        }
+        *current = scheduled.to_string();
+        self.name = scheduled.to_string();
+        true
+    }
+
+    fn poll_files(&mut self) -> bool {
+        if self.stamps.is_none() {
+            return false;
+        }
+        let stamps = Some(self.read_stamps());
+        if self.stamps == stamps {
+            return false;
+        }
+        self.stamps = stamps;
+        true
+    }
+
+    /// Built-in themes followed by user themes that do not shadow one
+    fn names(&self) -> Vec<String> {
+        let builtin = Theme::available_themes();
+        let user = self
+            .user_dir
+            .as_deref()
+            .map(Theme::user_themes)
+            .unwrap_or_default();
+        let user = user
+            .into_iter()
+            .filter(|name| !builtin.contains(&name.as_str()));
+        builtin
+            .iter()
+            .map(|name| name.to_string())
+            .chain(user)
+            .collect()
+    }
+
+    // The whole directory is watched since a theme can extend other user themes
+    fn read_stamps(&self) -> Vec<(PathBuf, Option<SystemTime>)> {
+        let Some(entries) = self
+            .user_dir
+            .as_deref()
+            .and_then(|dir| fs::read_dir(dir).ok())
+        else {
+            return Vec::new();
+        };
+        let mut stamps: Vec<_> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .map(|path| {
+                let modified = fs::metadata(&path).and_then(|meta| meta.modified()).ok();
+                (path, modified)
+            })
+            .collect();
+        stamps.sort();
+        stamps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Color;
+
+    #[test]
+    fn test_cycle_wraps_through_builtin_and_user_themes() {
+        let dir = std::env::temp_dir().join(format!("gitlogue-switcher-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("zz-mine.toml"), "extends = \"nord\"\n").unwrap();
+        fs::write(dir.join("nord.toml"), "extends = \"nord\"\n").unwrap();
+
+        let mut switcher = ThemeSwitcher::new(
+            "tokyo-night",
+            Some(dir.clone()),
+            true,
+            ColorDepth::TrueColor,
+        );
+        switcher.cycle(true).unwrap();
+        assert_eq!(switcher.name(), "zz-mine");
+        switcher.cycle(true).unwrap();
+        assert_eq!(switcher.name(), "ayu-dark");
+        switcher.cycle(false).unwrap();
+        assert_eq!(switcher.name(), "zz-mine");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_poll_changes_reloads_modified_theme() {
+        let dir = std::env::temp_dir().join(format!("gitlogue-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("mine.toml");
+        fs::write(&path, "separator = \"red\"\n").unwrap();
+
+        let mut switcher =
+            ThemeSwitcher::new("mine", Some(dir.clone()), false, ColorDepth::TrueColor).watch();
+        switcher.next_check = Instant::now();
+        assert!(switcher.poll_changes().is_none());
+
+        fs::write(&path, "separator = \"blue\"\n").unwrap();
+        // Make the change visible even on filesystems with coarse timestamps
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+        switcher.next_check = Instant::now();
+        let theme = switcher.poll_changes().unwrap().unwrap();
+        assert_eq!(theme.separator, Color::Blue);
+        assert_eq!(theme.background_left, Color::Reset);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_schedule_keeps_picked_theme_until_switch() {
+        let time = |h| NaiveTime::from_hms_opt(h, 0, 0).unwrap();
+        let schedule = ThemeSchedule {
+            day: "solarized-light".to_string(),
+            night: "tokyo-night".to_string(),
+            day_starts: time(7),
+            night_starts: time(19),
+        };
+
+        let mut switcher =
+            ThemeSwitcher::new("nord", None, true, ColorDepth::TrueColor).schedule(schedule);
+        switcher.schedule.as_mut().unwrap().1 = "tokyo-night".to_string();
+        switcher.name = "dracula".to_string();
+        // A manually picked theme survives until the next switch
+        assert!(!switcher.poll_schedule(time(23)));
+        assert_eq!(switcher.name(), "dracula");
+        assert!(switcher.poll_schedule(time(8)));
+        assert_eq!(switcher.name(), "solarized-light");
+    }
+}
@@ -1,37 +1,60 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind, KeyModifiers,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
-    backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    backend::{CrosstermBackend, TestBackend},
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect, Size},
     style::Style,
     text::{Line, Span},
-    widgets::{Block, Borders, Padding, Paragraph},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
     Frame, Terminal,
 };
 use std::io;
+use std::ops::Range;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::animation::AnimationEngine;
-use crate::git::{CommitMetadata, GitRepository};
+use crate::clock::{Clock, SystemClock, VirtualClock};
+use crate::git::CommitMetadata;
+use crate::history::PlaybackHistory;
 use crate::panes::{EditorPane, FileTreePane, StatusBarPane, TerminalPane};
+use crate::prefetch::{CommitPrefetcher, PreparedCommit};
 use crate::theme::Theme;
-use crate::PlaybackOrder;
-use unicode_width::UnicodeWidthStr;
+use crate::theme_switcher::ThemeSwitcher;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+// Typing speed bounds and step factor for live speed changes (ms per character)
+const MIN_SPEED_MS: u64 = 1;
+const MAX_SPEED_MS: u64 = 500;
+const SPEED_STEP_FACTOR: f64 = 1.25;
+
+// How long transient on-screen messages stay visible
+const OSD_DURATION: Duration = Duration::from_millis(1500);
 
 #[derive(Debug, Clone, PartialEq)]
 enum UIState {
     Playing,
-    WaitingForNext { resume_at: Instant },
+    Paused,
+    WaitingForNext {
+        resume_at: Instant,
+    },
+    /// Reloading the evicted history entry at `index` in the background
+    Loading {
+        index: usize,
+    },
     Finished,
 }
 
-pub struct UI<'a> {
+pub struct UI {
     state: UIState,
     speed_ms: u64,
     file_tree: FileTreePane,
@@ -39,44 +62,73 @@ pub struct UI<'a> {
     terminal: TerminalPane,
     status_bar: StatusBarPane,
     engine: AnimationEngine,
-    repo: Option<&'a GitRepository>,
+    source: Option<CommitPrefetcher>,
     should_exit: Arc<AtomicBool>,
     theme: Theme,
-    order: PlaybackOrder,
-    loop_playback: bool,
-    commit_spec: Option<String>,
-    is_range_mode: bool,
+    themes: ThemeSwitcher,
+    history: PlaybackHistory,
+    /// Selected entry while the history list is open
+    history_selection: Option<usize>,
+    /// Transient on-screen message (e.g. speed changes) and when it expires
+    osd_message: Option<(String, Instant)>,
+    /// Whether the current commit is cut short when new ones land (follow mode)
+    interruptible: bool,
+    /// Time source shared with the engine
+    clock: Rc<dyn Clock>,
 }
 
-impl<'a> UI<'a> {
+impl UI {
+    /// Create a UI starting with the current theme of `themes`
     pub fn new(
         speed_ms: u64,
-        repo: Option<&'a GitRepository>,
-        theme: Theme,
-        order: PlaybackOrder,
-        loop_playback: bool,
-        commit_spec: Option<String>,
-        is_range_mode: bool,
-    ) -> Self {
-        let should_exit = Arc::new(AtomicBool::new(false));
-        Self::setup_signal_handler(should_exit.clone());
-
-        Self {
+        source: Option<CommitPrefetcher>,
+        themes: ThemeSwitcher,
+    ) -> Result<Self> {
+        let clock: Rc<dyn Clock> = Rc::new(SystemClock);
+        let ui = Self::with_clock(speed_ms, clock, source, themes)?;
+        Self::setup_signal_handler(ui.should_exit.clone());
+        Ok(ui)
+    }
+
+    /// Create a UI for rendering off-screen, with the engine timed by `clock`
+    pub fn headless(speed_ms: u64, themes: ThemeSwitcher, clock: &VirtualClock) -> Result<Self> {
+        Self::with_clock(speed_ms, Rc::new(clock.clone()), None, themes)
+    }
+
+    fn with_clock(
+        speed_ms: u64,
+        clock: Rc<dyn Clock>,
+        source: Option<CommitPrefetcher>,
+        themes: ThemeSwitcher,
+    ) -> Result<Self> {
+        Ok(Self {
             state: UIState::Playing,
             speed_ms,
             file_tree: FileTreePane::new(),
             editor: EditorPane,
             terminal: TerminalPane,
-            status_bar: StatusBarPane,
-            engine: AnimationEngine::new(speed_ms),
-            repo,
-            should_exit,
-            theme,
-            order,
-            loop_playback,
-            commit_spec,
-            is_range_mode,
-        }
+            status_bar: StatusBarPane::new(),
+            engine: AnimationEngine::new(speed_ms, clock.clone()),
+            clock,
+            source,
+            should_exit: Arc::new(AtomicBool::new(false)),
+            theme: themes.load()?,
+            themes,
+            history: PlaybackHistory::new(),
+            history_selection: None,
+            osd_message: None,
+            interruptible: false,
+        })
+    }
+
+    /// Make typing delays reproducible across runs with the same seed
+    pub fn set_seed(&mut self, seed: u64) {
+        self.engine.set_seed(seed);
+    }
+
+    /// Show the active `--since`/`--until` window in the status bar
+    pub fn set_date_window(&mut self, label: String) {
+        self.status_bar.set_date_window(Some(label));
     }
 
     fn setup_signal_handler(should_exit: Arc<AtomicBool>) {
@@ -96,9 +148,263 @@ impl<'a> UI<'a> {
         .expect("Error setting Ctrl-C handler");
     }
 
+    /// Play a newly fetched commit and record it in the history
     pub fn load_commit(&mut self, metadata: CommitMetadata) {
         self.engine.load_commit(&metadata);
-        self.state = UIState::Playing;
+        self.history.push(metadata);
+        self.resume();
+    }
+
+    /// Play a commit prepared by the prefetcher and start preparing the one after it
+    pub fn load_prepared(&mut self, prepared: PreparedCommit) {
+        if prepared.live {
+            let branch = prepared.metadata.ref_name.as_deref().unwrap_or("HEAD");
+            self.show_osd(format!("new commit on {}", branch));
+        }
+        self.interruptible = !prepared.live;
+        self.engine
+            .load_steps(&prepared.metadata, prepared.steps, prepared.speed_ms);
+        self.history.push(prepared.metadata);
+        self.resume();
+
+        if let Some(source) = self.source.as_mut() {
+            source.prefetch(self.speed_ms);
+        }
+    }
+
+    /// Play the history entry at `index`, reloading its metadata if it was evicted
+    fn play_history_entry(&mut self, index: usize) {
+        let Some(entry) = self.history.select(index) else {
+            return;
+        };
+
+        // Commits picked by the user are not cut short by new ones
+        self.interruptible = false;
+        // The current commit stays on screen until the worker has built the steps of this one
+        if let Some(metadata) = entry.metadata() {
+            let metadata = metadata.clone();
+            let speed_ms = self.speed_ms;
+            match self.source.as_mut() {
+                Some(source) => match source.request_prepare(metadata, speed_ms) {
+                    Ok(()) => self.state = UIState::Loading { index },
+                    Err(_) => {
+                        let label = self.history.entries()[index].label.clone();
+                        self.show_osd(format!("failed to reload {}", label));
+                    }
+                },
+                None => {
+                    self.engine.load_commit(&metadata);
+                    self.resume();
+                }
+            }
+            return;
+        }
+
+        let hash = entry.hash.clone();
+        let label = entry.label.clone();
+        let speed_ms = self.speed_ms;
+        match self
+            .source
+            .as_mut()
+            .map(|source| source.request_commit(&hash, speed_ms))
+        {
+            Some(Ok(())) => {
+                self.show_osd(format!("loading {}", label));
+                self.state = UIState::Loading { index };
+            }
+            _ => self.show_osd(format!("failed to reload {}", label)),
+        }
+    }
+
+    /// Play the history entry reloaded by the worker once it is ready
+    fn poll_history_entry(&mut self, index: usize) {
+        let Some(result) = self.source.as_mut().and_then(|source| source.try_commit()) else {
+            return;
+        };
+        match result {
+            Ok(prepared) => {
+                self.engine
+                    .load_steps(&prepared.metadata, prepared.steps, prepared.speed_ms);
+                self.history.store(index, prepared.metadata);
+            }
+            Err(_) => {
+                let label = self.history.entries()[index].label.clone();
+                self.show_osd(format!("failed to reload {}", label));
+            }
+        }
+        self.resume();
+    }
+
+    /// Continue playback, keeping the engine paused if the user paused it
+    fn resume(&mut self) {
+        self.state = if self.engine.is_user_paused() {
+            UIState::Paused
+        } else {
+            UIState::Playing
+        };
+    }
+
+    fn show_osd(&mut self, message: String) {
+        self.osd_message = Some((message, self.clock.now() + OSD_DURATION));
+    }
+
+    fn toggle_pause(&mut self) {
+        match self.state {
+            UIState::Paused => {
+                self.engine.set_paused(false);
+                self.state = UIState::Playing;
+            }
+            UIState::Playing | UIState::WaitingForNext { .. } => {
+                self.engine.set_paused(true);
+                self.state = UIState::Paused;
+            }
+            // Loading finishes either way; the engine decides how it resumes
+            UIState::Loading { .. } => {
+                let paused = !self.engine.is_user_paused();
+                self.engine.set_paused(paused);
+            }
+            UIState::Finished => {}
+        }
+    }
+
+    fn change_speed(&mut self, faster: bool) {
+        let speed = self.engine.speed_ms() as f64;
+        let speed = if faster {
+            speed / SPEED_STEP_FACTOR
+        } else {
+            speed * SPEED_STEP_FACTOR
+        };
+        self.speed_ms = (speed.round() as u64).clamp(MIN_SPEED_MS, MAX_SPEED_MS);
+        self.engine.set_speed(self.speed_ms);
+        self.show_osd(format!("speed: {}ms", self.speed_ms));
+    }
+
+    fn cycle_theme(&mut self, forward: bool) {
+        let theme = self.themes.cycle(forward);
+        self.apply_theme(theme);
+    }
+
+    /// Switch to a rebuilt theme, or report why it could not be loaded
+    fn apply_theme(&mut self, theme: Result<Theme>) {
+        match theme {
+            Ok(theme) => {
+                self.theme = theme;
+                // The file tree caches styled lines
+                self.file_tree.invalidate();
+                self.show_osd(format!("theme: {}", self.themes.name()));
+            }
+            Err(e) => self.show_osd(format!("theme {}: {}", self.themes.name(), e.root_cause())),
+        }
+    }
+
+    fn skip_to_next_commit(&mut self) {
+        if self.history.has_next() {
+            if let Some(pos) = self.history.position() {
+                self.play_history_entry(pos + 1);
+            }
+        } else if self.source.is_some() && self.state != UIState::Finished {
+            self.state = UIState::WaitingForNext {
+                resume_at: self.clock.now(),
+            };
+        }
+    }
+
+    fn replay_previous_commit(&mut self) {
+        // Restart the current commit if nothing was played before it
+        if let Some(pos) = self.history.position() {
+            self.play_history_entry(pos.saturating_sub(1));
+        }
+    }
+
+    /// Handle a key press while the history list is open
+    fn handle_history_key(&mut self, code: KeyCode, selected: usize) {
+        let len = self.history.entries().len();
+        match code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                // The list shows the newest entry first
+                self.history_selection = Some((selected + 1).min(len.saturating_sub(1)));
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.history_selection = Some(selected.saturating_sub(1));
+            }
+            KeyCode::Enter => {
+                self.history_selection = None;
+                self.play_history_entry(selected);
+            }
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('q') => {
+                self.history_selection = None;
+            }
+            _ => {}
+        }
+    }
+
+    /// Handle a key press, returning true if the screen needs a redraw
+    fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers) -> bool {
+        if let Some(selected) = self.history_selection {
+            if !(code == KeyCode::Char('c') && modifiers.contains(KeyModifiers::CONTROL)) {
+                self.handle_history_key(code, selected);
+                return true;
+            }
+        }
+
+        match code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.state = UIState::Finished;
+                false
+            }
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.state = UIState::Finished;
+                false
+            }
+            KeyCode::Char(' ') => {
+                self.toggle_pause();
+                true
+            }
+            KeyCode::Char('.') if self.state == UIState::Paused => self.engine.step_forward(),
+            KeyCode::Char(']') => {
+                let skipped = self.engine.skip_to_next_file();
+                if skipped && matches!(self.state, UIState::WaitingForNext { .. }) {
+                    self.resume();
+                }
+                skipped
+            }
+            KeyCode::Char('[') => {
+                let skipped = self.engine.skip_to_previous_file();
+                if skipped && matches!(self.state, UIState::WaitingForNext { .. }) {
+                    self.resume();
+                }
+                skipped
+            }
+            KeyCode::Char('n') => {
+                self.skip_to_next_commit();
+                true
+            }
+            KeyCode::Char('p') => {
+                self.replay_previous_commit();
+                true
+            }
+            KeyCode::Char('h') => {
+                self.history_selection = self.history.position();
+                true
+            }
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.change_speed(true);
+                true
+            }
+            KeyCode::Char('-') => {
+                self.change_speed(false);
+                true
+            }
+            KeyCode::Char('t') => {
+                self.cycle_theme(true);
+                true
+            }
+            KeyCode::Char('T') => {
+                self.cycle_theme(false);
+                true
+            }
+            _ => false,
+        }
     }
 
     pub fn run(&mut self) -> Result<()> {
@@ -134,45 +440,49 @@ impl<'a> UI<'a> {
             }
 
             // Update viewport dimensions for scroll calculation
-            let size = terminal.size()?;
-            // Editor area: 70% (right column) × 80% (editor pane) = 56% of total height
-            let viewport_height = (size.height as f32 * 0.70 * 0.80) as usize;
-            // Editor width: 70% (right column)
-            let content_width = (size.width as f32 * 0.70) as usize;
-            self.engine.set_viewport_height(viewport_height);
-            self.engine.set_content_width(content_width);
+            self.update_viewport(terminal.size()?);
 
             // Tick the animation engine
-            let needs_redraw = self.engine.tick();
+            let mut needs_redraw = self.engine.tick();
 
-            if needs_redraw {
-                terminal.draw(|f| self.render(f))?;
+            if let Some(theme) = self.themes.poll_changes() {
+                self.apply_theme(theme);
+                needs_redraw = true;
             }
 
             // Poll for keyboard events at frame rate
             if event::poll(std::time::Duration::from_millis(8))? {
                 if let Event::Key(key) = event::read()? {
-                    match key.code {
-                        KeyCode::Esc | KeyCode::Char('q') => {
-                            self.state = UIState::Finished;
-                        }
-                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            self.state = UIState::Finished;
-                        }
-                        _ => {}
+                    if key.kind == KeyEventKind::Press {
+                        needs_redraw |= self.handle_key(key.code, key.modifiers);
                     }
                 }
             }
 
+            if needs_redraw {
+                terminal.draw(|f| self.render(f))?;
+            }
+
             // State machine
             match self.state {
+                UIState::Paused => {}
                 UIState::Playing => {
-                    if self.engine.is_finished() {
-                        if self.repo.is_some() {
+                    let live_pending = self.interruptible
+                        && self
+                            .source
+                            .as_mut()
+                            .is_some_and(|source| source.live_pending());
+                    if live_pending {
+                        // Historical playback gives way to commits that just landed
+                        self.state = UIState::WaitingForNext {
+                            resume_at: self.clock.now(),
+                        };
+                    } else if self.engine.is_finished() {
+                        if self.source.is_some() {
                             // Schedule next commit
                             // Wait time proportional to speed (100x the typing speed)
                             self.state = UIState::WaitingForNext {
-                                resume_at: Instant::now()
+                                resume_at: self.clock.now()
                                     + Duration::from_millis(self.speed_ms * 100),
                             };
                         } else {
@@ -182,63 +492,23 @@ impl<'a> UI<'a> {
                     }
                 }
                 UIState::WaitingForNext { resume_at } => {
-                    if Instant::now() >= resume_at {
-                        if let Some(repo) = self.repo {
-                            let result = if self.is_range_mode {
-                                match self.order {
-                                    PlaybackOrder::Random => repo.random_range_commit(),
-                                    PlaybackOrder::Asc => repo.next_range_commit_asc(),
-                                    PlaybackOrder::Desc => repo.next_range_commit_desc(),
-                                }
-                            } else if self.commit_spec.is_some() {
-                                repo.get_commit(self.commit_spec.as_ref().unwrap())
-                            } else {
-                                match self.order {
-                                    PlaybackOrder::Random => repo.random_commit(),
-                                    PlaybackOrder::Asc => repo.next_asc_commit(),
-                                    PlaybackOrder::Desc => repo.next_desc_commit(),
-                                }
-                            };
-                            match result {
-                                Ok(metadata) => {
-                                    self.load_commit(metadata);
-                                }
-                                Err(_) => {
-                                    if self.loop_playback {
-                                        repo.reset_index();
-                                        let restart_result = if self.is_range_mode {
-                                            match self.order {
-                                                PlaybackOrder::Random => repo.random_range_commit(),
-                                                PlaybackOrder::Asc => repo.next_range_commit_asc(),
-                                                PlaybackOrder::Desc => {
-                                                    repo.next_range_commit_desc()
-                                                }
-                                            }
-                                        } else {
-                                            match self.order {
-                                                PlaybackOrder::Random => repo.random_commit(),
-                                                PlaybackOrder::Asc => repo.next_asc_commit(),
-                                                PlaybackOrder::Desc => repo.next_desc_commit(),
-                                            }
-                                        };
-                                        match restart_result {
-                                            Ok(metadata) => {
-                                                self.load_commit(metadata);
-                                            }
-                                            Err(_) => {
-                                                self.state = UIState::Finished;
-                                            }
-                                        }
-                                    } else {
-                                        self.state = UIState::Finished;
-                                    }
-                                }
+                    if self.clock.now() >= resume_at {
+                        if self.history.has_next() {
+                            // Continue through commits revisited from the history first
+                            self.skip_to_next_commit();
+                        } else if let Some(source) = self.source.as_mut() {
+                            // Keep rendering until the prefetched commit is ready
+                            match source.try_next(self.speed_ms) {
+                                Some(Ok(prepared)) => self.load_prepared(prepared),
+                                Some(Err(_)) => self.state = UIState::Finished,
+                                None => {}
                             }
                         } else {
                             self.state = UIState::Finished;
                         }
                     }
                 }
+                UIState::Loading { index } => self.poll_history_entry(index),
                 UIState::Finished => {
                     break;
                 }
@@ -248,6 +518,50 @@ impl<'a> UI<'a> {
         Ok(())
     }
 
+    /// Play the loaded commit to the end on `clock` instead of wall time
+    /// Each redrawn frame is passed to `on_frame` with the time it would appear at
+    /// and the steps run since the previous frame; the clock is left at the end of playback
+    pub fn run_headless(
+        &mut self,
+        terminal: &mut Terminal<TestBackend>,
+        clock: &VirtualClock,
+        mut on_frame: impl FnMut(Duration, &Buffer, Range<usize>) -> Result<()>,
+    ) -> Result<()> {
+        self.update_viewport(terminal.size()?);
+
+        let mut needs_redraw = true;
+        let mut drawn_steps = self.engine.current_step();
+        loop {
+            needs_redraw |= self.engine.tick();
+            if needs_redraw {
+                terminal.draw(|f| self.render(f))?;
+                let steps = drawn_steps..self.engine.current_step();
+                drawn_steps = steps.end;
+                on_frame(clock.elapsed(), terminal.backend().buffer(), steps)?;
+                needs_redraw = false;
+            }
+
+            if self.engine.is_finished() {
+                // Hold the last frame through the closing pause
+                if let Some(pause_until) = self.engine.pause_until() {
+                    clock.advance_to(pause_until);
+                }
+                return Ok(());
+            }
+            // Nothing changes on screen until the engine's next deadline
+            clock.advance_to(self.engine.next_deadline());
+        }
+    }
+
+    fn update_viewport(&mut self, size: Size) {
+        // Editor area: 70% (right column) × 80% (editor pane) = 56% of total height
+        let viewport_height = (size.height as f32 * 0.70 * 0.80) as usize;
+        // Editor width: 70% (right column)
+        let content_width = (size.width as f32 * 0.70) as usize;
+        self.engine.set_viewport_height(viewport_height);
+        self.engine.set_content_width(content_width);
+    }
+
     fn render(&mut self, f: &mut Frame) {
         let size = f.area();
 
@@ -351,11 +665,30 @@ impl<'a> UI<'a> {
 
             // Calculate content width (dialog_width - borders(2) - padding(2))
             let content_width = dialog_width.saturating_sub(4) as usize;
-            let padding_len = content_width.saturating_sub(text_display_width);
+
+            // On narrow screens scroll to the end of the text, keeping a cell for the cursor
+            let visible_text = if text_display_width < content_width {
+                text.clone()
+            } else {
+                let mut tail_width = 0;
+                let tail: String = text
+                    .chars()
+                    .rev()
+                    .take_while(|ch| {
+                        tail_width += ch.width().unwrap_or(0);
+                        tail_width + 2 <= content_width
+                    })
+                    .collect::<Vec<_>>()
+                    .into_iter()
+                    .rev()
+                    .collect();
+                format!("…{}", tail)
+            };
+            let padding_len = content_width.saturating_sub(visible_text.width());
 
             let spans = vec![
                 Span::styled(
-                    text.clone(),
+                    visible_text,
                     Style::default().fg(self.theme.file_tree_current_file_fg),
                 ),
                 Span::styled(
@@ -379,5 +712,422 @@ impl<'a> UI<'a> {
             let dialog = Paragraph::new(dialog_text).block(block);
             f.render_widget(dialog, dialog_area);
         }
+
+        self.render_history(f, size);
+        self.render_osd(f, right_layout[0]);
+    }
+
+    /// Render the list of played commits, newest first
+    fn render_history(&self, f: &mut Frame, size: Rect) {
+        let Some(selected) = self.history_selection else {
+            return;
+        };
+
+        let width = 72.min(size.width);
+        let height = 16.min(size.height);
+        let area = Rect {
+            x: (size.width.saturating_sub(width)) / 2,
+            y: (size.height.saturating_sub(height)) / 2,
+            width,
+            height,
+        };
+
+        let entries = self.history.entries();
+        let visible = height.saturating_sub(2) as usize;
+        // Rows are listed newest first; keep the selected row in view
+        let selected_row = entries.len().saturating_sub(1) - selected.min(entries.len() - 1);
+        let first_row = selected_row.saturating_sub(visible.saturating_sub(1));
+
+        let lines: Vec<Line> = entries
+            .iter()
+            .enumerate()
+            .rev()
+            .skip(first_row)
+            .take(visible)
+            .map(|(index, entry)| {
+                let marker = if Some(index) == self.history.position() {
+                    "▶ "
+                } else {
+                    "  "
+                };
+                let style = if index == selected {
+                    Theme::highlight(
+                        self.theme.file_tree_current_file_bg,
+                        self.theme.file_tree_current_file_fg,
+                    )
+                } else {
+                    Style::default().fg(self.theme.file_tree_default)
+                };
+                Line::from(vec![
+                    Span::styled(marker, style),
+                    Span::styled(entry.label.clone(), style.fg(self.theme.status_hash)),
+                    Span::styled(format!(" {} ", entry.summary), style),
+                    Span::styled(entry.author.clone(), style.fg(self.theme.status_author)),
+                ])
+            })
+            .collect();
+
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .title(" History (↑/↓ select, Enter replay, Esc close) ")
+            .style(
+                Style::default()
+                    .fg(self.theme.file_tree_current_file_fg)
+                    .bg(self.theme.background_left),
+            );
+
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Render playback indicators (paused, speed changes) in the top-right corner of the editor
+    fn render_osd(&mut self, f: &mut Frame, area: Rect) {
+        if let Some((_, expires_at)) = &self.osd_message {
+            if self.clock.now() >= *expires_at {
+                self.osd_message = None;
+            }
+        }
+
+        let mut labels = Vec::new();
+        if self.state == UIState::Paused {
+            labels.push("⏸ paused".to_string());
+        }
+        if let Some((message, _)) = &self.osd_message {
+            labels.push(message.clone());
+        }
+        if labels.is_empty() {
+            return;
+        }
+
+        let text = format!(" {} ", labels.join(" · "));
+        let width = (text.width() as u16).min(area.width);
+        let osd_area = Rect {
+            x: area.x + area.width.saturating_sub(width),
+            y: area.y,
+            width,
+            height: 1.min(area.height),
+        };
+        let osd = Paragraph::new(Line::from(text)).style(
+            Style::default()
+                .fg(self.theme.file_tree_current_file_fg)
+                .bg(self.theme.editor_cursor_line_bg),
+        );
+        f.render_widget(osd, osd_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::AnimationStep;
+    use crate::git::fixture::Fixture;
+    use crate::theme::ColorDepth;
+    use ratatui::backend::TestBackend;
+    use ratatui::style::Modifier;
+    use std::collections::HashMap;
+
+    /// Replay `metadata` headless on a virtual clock and keep the screen right after each
+    /// step named by `marks`, which picks step indices from the commit's steps
+    fn snapshots(
+        metadata: CommitMetadata,
+        color_depth: ColorDepth,
+        width: u16,
+        height: u16,
+        marks: impl FnOnce(&[AnimationStep]) -> Vec<(&'static str, usize)>,
+    ) -> HashMap<&'static str, Buffer> {
+        let clock = VirtualClock::new();
+        let mut terminal = Terminal::new(TestBackend::new(width, height)).unwrap();
+        let themes = ThemeSwitcher::new("tokyo-night", None, true, color_depth);
+        let mut ui = UI::headless(30, themes, &clock).unwrap();
+        ui.set_seed(1);
+        ui.load_commit(metadata);
+
+        let marks = marks(ui.engine.steps());
+        let mut screens = HashMap::new();
+        ui.run_headless(&mut terminal, &clock, |_, buffer, steps| {
+            for &(name, index) in &marks {
+                if steps.contains(&index) {
+                    // Steps shorter than a frame run together; the screen would show later ones too
+                    assert_eq!(steps.end, index + 1, "step {:?} ran in a batch", name);
+                    screens.insert(name, buffer.clone());
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+
+        for (name, _) in marks {
+            assert!(screens.contains_key(name), "step {:?} never ran", name);
+        }
+        screens
+    }
+
+    /// Index of the `n`th step (from 0) matching `predicate`
+    fn nth_step(
+        steps: &[AnimationStep],
+        n: usize,
+        predicate: impl Fn(&AnimationStep) -> bool,
+    ) -> usize {
+        steps
+            .iter()
+            .enumerate()
+            .filter(|(_, step)| predicate(step))
+            .nth(n)
+            .map(|(index, _)| index)
+            .unwrap()
+    }
+
+    fn is_switch_file(step: &AnimationStep) -> bool {
+        matches!(step, AnimationStep::SwitchFile { .. })
+    }
+
+    /// Text of each screen row as a terminal shows it, without trailing blanks
+    fn rows(buffer: &Buffer) -> Vec<String> {
+        let area = buffer.area;
+        (area.top()..area.bottom())
+            .map(|y| {
+                let mut row = String::new();
+                let mut x = area.left();
+                while x < area.right() {
+                    let symbol = buffer[(x, y)].symbol();
+                    row.push_str(symbol);
+                    // Cells under a wide character are hidden, whatever they hold
+                    x += symbol.width().max(1) as u16;
+                }
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    fn is_output(step: &AnimationStep) -> bool {
+        matches!(step, AnimationStep::TerminalOutput { .. })
+    }
+
+    /// "Greet by name": edits README.md and src/main.rs
+    fn two_file_commit(fixture: &Fixture) -> CommitMetadata {
+        fixture.commit(
+            "Add greeting",
+            &[
+                ("src/main.rs", "fn main() {\n    run();\n}\n"),
+                ("README.md", "# Demo\n"),
+            ],
+        );
+        let hash = fixture.commit(
+            "Greet by name",
+            &[
+                (
+                    "src/main.rs",
+                    "fn main() {\n    let name = \"world\";\n    greet(name);\n}\n",
+                ),
+                ("README.md", "# Demo\n\nSays hello.\n"),
+            ],
+        );
+        fixture.metadata(&hash)
+    }
+
+    const LONG_PATH: &str = "src/components/navigation/sidebar/SidebarNavigationItem.tsx";
+
+    /// Adds a file with a deep path and a line too long for a narrow editor
+    fn long_file_commit(fixture: &Fixture) -> CommitMetadata {
+        let hash = fixture.commit(
+            "Add sidebar item",
+            &[(
+                LONG_PATH,
+                "export const item = {\n  label: \"Home\", href: \"/\", icon: \"house\", badge: 3, active: true };\n",
+            )],
+        );
+        fixture.metadata(&hash)
+    }
+
+    #[test]
+    fn test_snapshot_after_switch_file() {
+        let fixture = Fixture::new("snapshot-switch");
+        let screens = snapshots(
+            two_file_commit(&fixture),
+            ColorDepth::TrueColor,
+            80,
+            24,
+            |steps| vec![("switch", nth_step(steps, 0, is_switch_file))],
+        );
+
+        // README.md is open with its old content; the time travel output is still shown
+        assert_eq!(
+            rows(&screens["switch"]),
+            vec![
+                "",
+                "  ~ README.md +2 -0         1   # Demo",
+                "  src/",
+                "    ~ main.rs +2 -1",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "────────────────────────────────────────────────────────────────────────────────",
+                "",
+                "  hash: 7040439           ✨ Warping through spacetime...",
+                "  author: Ghost           🕰\u{fe0f}  Arrived at 2023-11-14 22:13:20",
+                "  date: 2023-11-14 22:13  📍 Location: commit 7040439 by Ghost",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_after_git_push() {
+        let fixture = Fixture::new("snapshot-push");
+        let screens = snapshots(
+            two_file_commit(&fixture),
+            ColorDepth::TrueColor,
+            80,
+            24,
+            |steps| {
+                let last_output = steps.iter().rposition(is_output).unwrap();
+                vec![("pushed", last_output)]
+            },
+        );
+
+        // The pushed range starts at the first parent ("Add greeting")
+        assert_eq!(
+            rows(&screens["pushed"]),
+            vec![
+                "",
+                "  ~ README.md +2 -0         1   fn main() {",
+                "  src/                      2       let name = \"world\";",
+                "    ~ main.rs +2 -1         3       greet(name);",
+                "                            4   }",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "────────────────────────────────────────────────────────────────────────────────",
+                "",
+                "  hash: 7040439           ✍\u{fe0f}  Signing with invisible ink: done.",
+                "  author: Ghost           📡 Beaming to origin/main via satellite...",
+                "  date: 2023-11-14 22:13     0731127..7040439 ✨ SUCCESS",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mono_selection_is_reversed() {
+        let fixture = Fixture::new("snapshot-mono");
+        let screens = snapshots(
+            two_file_commit(&fixture),
+            ColorDepth::Mono,
+            80,
+            24,
+            |steps| vec![("switch", nth_step(steps, 0, is_switch_file))],
+        );
+
+        // Row 1 holds both the current file in the tree and the cursor line in the editor
+        let buffer = &screens["switch"];
+        let reversed = |y: u16| {
+            (0..80u16)
+                .filter(|&x| buffer[(x, y)].modifier.contains(Modifier::REVERSED))
+                .count()
+        };
+        assert_eq!(reversed(1), 80);
+        assert_eq!(reversed(2), 0);
+    }
+
+    #[test]
+    fn test_snapshot_dialog_fits_narrow_screen() {
+        let fixture = Fixture::new("snapshot-dialog");
+        let screens = snapshots(
+            long_file_commit(&fixture),
+            ColorDepth::TrueColor,
+            48,
+            16,
+            |steps| {
+                let typing = nth_step(steps, 0, |step| {
+                    matches!(step, AnimationStep::DialogTypeChar { .. })
+                });
+                vec![("typed", typing + LONG_PATH.len() - 1)]
+            },
+        );
+
+        // The dialog fits the screen and scrolls to keep the end of the path in view
+        assert_eq!(
+            rows(&screens["typed"]),
+            vec![
+                "",
+                "  src/componen    1",
+                "  ts/navigatio",
+                "  n/sidebar/",
+                "    + SidebarN",
+                "  avigationIte",
+                "┌Open File...──────────────────────────────────┐",
+                "│ …vigation/sidebar/SidebarNavigationItem.tsx  │",
+                "└──────────────────────────────────────────────┘",
+                "",
+                "",
+                "",
+                "────────────────────────────────────────────────",
+                "",
+                "  hash: 7e04e2  📍 Location: commit 7e04e2d by G",
+                "",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_snapshot_long_lines_wrap() {
+        let fixture = Fixture::new("snapshot-wrap");
+        let screens = snapshots(
+            long_file_commit(&fixture),
+            ColorDepth::TrueColor,
+            48,
+            16,
+            |steps| {
+                let last_insert = steps
+                    .iter()
+                    .rposition(|step| matches!(step, AnimationStep::InsertChar { .. }))
+                    .unwrap();
+                vec![("typed", last_insert)]
+            },
+        );
+
+        // File tree lines continue inside the padding, editor lines past the gutter
+        assert_eq!(
+            rows(&screens["typed"]),
+            vec![
+                "",
+                "  src/componen    1   export const item = {",
+                "  ts/navigatio    2     label: \"Home\", href: \"/\"",
+                "  n/sidebar/          , icon: \"house\", badge: 3,",
+                "    + SidebarN         active: true };",
+                "  avigationIte    3",
+                "  m.tsx +2 -0",
+                "",
+                "",
+                "",
+                "",
+                "",
+                "────────────────────────────────────────────────",
+                "",
+                "  hash: 7e04e2  📍 Location: commit 7e04e2d by G",
+                "",
+            ]
+        );
     }
 }
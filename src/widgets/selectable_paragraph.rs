@@ -17,6 +17,7 @@ pub struct SelectableParagraph<'a> {
     selected_style: Style,
     background_style: Style,
     padding: Padding,
+    wrap_indent: u16,
     dim_max_distance: Option<usize>,
     dim_min_opacity: f32,
 }
@@ -30,6 +31,7 @@ impl<'a> SelectableParagraph<'a> {
             selected_style: Style::default(),
             background_style: Style::default(),
             padding: Padding::ZERO,
+            wrap_indent: 0,
             dim_max_distance: None,
             dim_min_opacity: 0.6,
         }
@@ -60,6 +62,13 @@ impl<'a> SelectableParagraph<'a> {
         self
     }
 
+    /// Indent wrapped continuation lines past the left padding, e.g. to clear
+    /// a line number gutter
+    pub fn wrap_indent(mut self, width: u16) -> Self {
+        self.wrap_indent = width;
+        self
+    }
+
     pub fn dim(mut self, max_distance: usize, min_opacity: f32) -> Self {
         self.dim_max_distance = Some(max_distance);
         self.dim_min_opacity = min_opacity;
@@ -211,7 +220,9 @@ impl<'a> Widget for SelectableParagraph<'a> {
 
         // For wrapping: first line uses full width minus left padding (no right padding when wrapping)
         let first_line_width = inner_area.width.saturating_sub(self.padding.left) as usize;
-        let continuation_width = inner_area.width as usize;
+        // Continuation lines keep the left padding and indent
+        let continuation_start = (self.padding.left + self.wrap_indent).min(inner_area.width);
+        let continuation_width = (inner_area.width - continuation_start).max(1) as usize;
         let no_wrap_content_width = inner_area
             .width
             .saturating_sub(self.padding.left + self.padding.right)
@@ -315,6 +326,7 @@ impl<'a> Widget for SelectableParagraph<'a> {
                         if span.style.fg.is_none() {
                             style.fg = self.selected_style.fg;
                         }
+                        style = style.add_modifier(self.selected_style.add_modifier);
                     }
 
                     // Apply dim to foreground color
@@ -376,6 +388,7 @@ impl<'a> Widget for SelectableParagraph<'a> {
                         if span.style.fg.is_none() {
                             style.fg = self.selected_style.fg;
                         }
+                        style = style.add_modifier(self.selected_style.add_modifier);
                     }
 
                     // Apply dim to foreground color
@@ -394,7 +407,7 @@ impl<'a> Widget for SelectableParagraph<'a> {
                 }
 
                 // Fill remaining space to right edge (no right padding)
-                let remaining_width = continuation_width.saturating_sub(self.padding.left as usize);
+                let remaining_width = first_line_width;
                 for x in x_pos..remaining_width {
                     if let Some(cell) =
                         buf.cell_mut((inner_area.x + self.padding.left + x as u16, render_y))
@@ -403,7 +416,13 @@ impl<'a> Widget for SelectableParagraph<'a> {
                     }
                 }
             } else {
-                // Wrapped continuation line: use full width (no padding)
+                // Wrapped continuation line: left padding and indent only
+                for x in 0..continuation_start {
+                    if let Some(cell) = buf.cell_mut((inner_area.x + x, render_y)) {
+                        cell.set_style(fill_style);
+                    }
+                }
+
                 let mut x_pos = 0;
 
                 for span in &line.spans {
@@ -417,6 +436,7 @@ impl<'a> Widget for SelectableParagraph<'a> {
                         if span.style.fg.is_none() {
                             style.fg = self.selected_style.fg;
                         }
+                        style = style.add_modifier(self.selected_style.add_modifier);
                     }
 
                     // Apply dim to foreground color
@@ -425,7 +445,7 @@ impl<'a> Widget for SelectableParagraph<'a> {
                     }
 
                     buf.set_string(
-                        inner_area.x + x_pos as u16,
+                        inner_area.x + continuation_start + x_pos as u16,
                         render_y,
                         span.content.as_ref(),
                         style,
@@ -434,9 +454,11 @@ impl<'a> Widget for SelectableParagraph<'a> {
                     x_pos += span.content.width();
                 }
 
-                // Fill remaining space to full width
+                // Fill remaining space to right edge
                 for x in x_pos..continuation_width {
-                    if let Some(cell) = buf.cell_mut((inner_area.x + x as u16, render_y)) {
+                    if let Some(cell) =
+                        buf.cell_mut((inner_area.x + continuation_start + x as u16, render_y))
+                    {
                         cell.set_style(fill_style);
                     }
                 }